@@ -0,0 +1,9 @@
+--
+-- Name: key_encryption_keys backend; Type: COLUMN; Schema: public; Owner: -
+--
+-- Selects the key wrapping backend for a KEK. `kms_key` holds the backend's key
+-- reference: a KMS key ID/ARN for `aws_kms`, a free-form label for `local`.
+--
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD COLUMN backend text DEFAULT 'aws_kms'::text NOT NULL;
@@ -0,0 +1,19 @@
+--
+-- Name: key_encryption_keys state; Type: COLUMN; Schema: public; Owner: -
+--
+-- Only `active` KEKs wrap new DEKs. DEKs of `decrypt_only` KEKs are re-wrapped under an
+-- active KEK by the rotation worker, which then marks the KEK `retired`.
+--
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD COLUMN state text DEFAULT 'active'::text NOT NULL;
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD CONSTRAINT key_encryption_keys_state_check CHECK (state IN ('active', 'decrypt_only', 'retired'));
+
+
+--
+-- Name: idx_key_encryption_keys_state; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX idx_key_encryption_keys_state ON public.key_encryption_keys USING btree (state);
@@ -0,0 +1,10 @@
+--
+-- Name: data_encryption_keys encryption_context; Type: COLUMN; Schema: public; Owner: -
+--
+-- The encryption context a DEK was wrapped under and its payload was bound to (as
+-- AES-GCM associated data). NULL marks DEKs created before contexts were introduced;
+-- their payloads are re-encrypted by the encryption context backfill.
+--
+
+ALTER TABLE ONLY public.data_encryption_keys
+    ADD COLUMN encryption_context jsonb;
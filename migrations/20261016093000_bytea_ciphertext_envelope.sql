@@ -0,0 +1,16 @@
+--
+-- Store ciphertexts and wrapped DEKs as bytea.
+--
+-- Wrapped DEKs were always hex, so they are decoded in place. Existing payloads keep their
+-- legacy hex text as bytes: they never start with the envelope version byte (0x01), which
+-- is how `crypto::decrypt` tells the two formats apart.
+--
+
+ALTER TABLE ONLY public.data_encryption_keys
+    ALTER COLUMN encrypted_key TYPE bytea USING decode(encrypted_key, 'hex');
+
+ALTER TABLE ONLY public.secret_versions
+    ALTER COLUMN encrypted_secret TYPE bytea USING convert_to(encrypted_secret, 'UTF8');
+
+ALTER TABLE ONLY public.vault_connections
+    ALTER COLUMN encrypted_config TYPE bytea USING convert_to(encrypted_config, 'UTF8');
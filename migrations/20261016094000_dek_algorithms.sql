@@ -0,0 +1,9 @@
+--
+-- Name: data_encryption_keys algo; Type: CONSTRAINT; Schema: public; Owner: -
+--
+-- `algo` selects the AEAD a DEK's payload is sealed with and must match the algorithm id
+-- in the payload's envelope header.
+--
+
+ALTER TABLE ONLY public.data_encryption_keys
+    ADD CONSTRAINT data_encryption_keys_algo_check CHECK (algo IN ('AES-256-GCM', 'XCHACHA20-POLY1305'));
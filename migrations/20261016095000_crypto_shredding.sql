@@ -0,0 +1,54 @@
+--
+-- Name: secret_versions destroyed_at; Type: COLUMN; Schema: public; Owner: -
+--
+-- A destroyed version keeps its row, so its tag cannot be reused, but its ciphertext and
+-- fingerprint are cleared and its DEK is deleted, leaving `dek_id` NULL.
+--
+
+ALTER TABLE ONLY public.secret_versions
+    ADD COLUMN destroyed_at timestamp with time zone;
+
+ALTER TABLE ONLY public.secret_versions
+    ALTER COLUMN dek_id DROP NOT NULL;
+
+ALTER TABLE ONLY public.secret_versions
+    ADD CONSTRAINT secret_versions_destroyed_check CHECK ((destroyed_at IS NULL) = (dek_id IS NOT NULL));
+
+
+--
+-- Name: shred_records; Type: TABLE; Schema: public; Owner: -
+--
+-- One row per DEK deleted to make a secret version unrecoverable. Rows have no foreign
+-- keys so they outlive the secret, version, DEK and KEK they describe.
+--
+
+CREATE TABLE public.shred_records (
+    id integer NOT NULL,
+    secret_name text NOT NULL,
+    version_tag text NOT NULL,
+    dek_key_id text NOT NULL,
+    kek_id integer NOT NULL,
+    shredded_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE SEQUENCE public.shred_records_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.shred_records_id_seq OWNED BY public.shred_records.id;
+
+ALTER TABLE ONLY public.shred_records ALTER COLUMN id SET DEFAULT nextval('public.shred_records_id_seq'::regclass);
+
+ALTER TABLE ONLY public.shred_records
+    ADD CONSTRAINT shred_records_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: idx_shred_records_secret_name; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX idx_shred_records_secret_name ON public.shred_records USING btree (secret_name);
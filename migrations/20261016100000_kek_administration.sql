@@ -0,0 +1,59 @@
+--
+-- Name: key_encryption_keys weight; Type: COLUMN; Schema: public; Owner: -
+--
+-- New DEKs are wrapped under an active KEK chosen at random in proportion to its weight.
+-- An active KEK with weight 0 still unwraps its DEKs but is never chosen for new ones.
+--
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD COLUMN weight integer DEFAULT 1 NOT NULL;
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD CONSTRAINT key_encryption_keys_weight_check CHECK (weight >= 0);
+
+
+--
+-- Name: key_encryption_keys verified_at; Type: COLUMN; Schema: public; Owner: -
+--
+-- When the KEK last completed a generate/unwrap round-trip through its backend.
+--
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD COLUMN verified_at timestamp with time zone;
+
+
+--
+-- Name: key_encryption_keys duplicates; Type: DATA; Schema: public; Owner: -
+--
+-- Rows registering the same backend key are merged into one before the unique constraint
+-- is added: the most usable row (active, then decrypt_only, then the lowest id) is kept,
+-- the DEKs wrapped under the others are moved to it and the others are deleted.
+--
+
+CREATE TEMPORARY TABLE key_encryption_key_duplicates ON COMMIT DROP AS
+    SELECT id, keep_id
+    FROM (
+        SELECT id, first_value(id) OVER (
+            PARTITION BY backend, kms_key
+            ORDER BY CASE state WHEN 'active' THEN 0 WHEN 'decrypt_only' THEN 1 ELSE 2 END, id
+        ) AS keep_id
+        FROM public.key_encryption_keys
+    ) ranked
+    WHERE id <> keep_id;
+
+UPDATE public.data_encryption_keys
+    SET kek_id = duplicates.keep_id
+    FROM key_encryption_key_duplicates duplicates
+    WHERE data_encryption_keys.kek_id = duplicates.id;
+
+DELETE FROM public.key_encryption_keys
+    USING key_encryption_key_duplicates duplicates
+    WHERE key_encryption_keys.id = duplicates.id;
+
+
+--
+-- Name: key_encryption_keys key_encryption_keys_backend_kms_key_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.key_encryption_keys
+    ADD CONSTRAINT key_encryption_keys_backend_kms_key_key UNIQUE (backend, kms_key);
@@ -0,0 +1,19 @@
+--
+-- Name: seal_config; Type: TABLE; Schema: public; Owner: -
+--
+-- Single row describing the Shamir split of the master key used by the `shamir` key
+-- wrapping backend. The master key and its shares are never stored.
+--
+
+CREATE TABLE public.seal_config (
+    id boolean DEFAULT true NOT NULL,
+    threshold integer NOT NULL,
+    share_count integer NOT NULL,
+    key_check bytea NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT seal_config_singleton CHECK (id),
+    CONSTRAINT seal_config_threshold_check CHECK (threshold BETWEEN 1 AND share_count)
+);
+
+ALTER TABLE ONLY public.seal_config
+    ADD CONSTRAINT seal_config_pkey PRIMARY KEY (id);
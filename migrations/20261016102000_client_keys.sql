@@ -0,0 +1,35 @@
+--
+-- Name: client_keys; Type: TABLE; Schema: public; Owner: -
+--
+-- Verifying keys of API clients. Requests name the key they are signed with in the
+-- `X-Key-Id` header; expired or revoked keys are rejected.
+--
+
+CREATE TABLE public.client_keys (
+    id integer NOT NULL,
+    key_id text NOT NULL,
+    name text NOT NULL,
+    public_key text NOT NULL,
+    expires_at timestamp with time zone,
+    revoked boolean DEFAULT false NOT NULL,
+    revoked_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE SEQUENCE public.client_keys_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.client_keys_id_seq OWNED BY public.client_keys.id;
+
+ALTER TABLE ONLY public.client_keys ALTER COLUMN id SET DEFAULT nextval('public.client_keys_id_seq'::regclass);
+
+ALTER TABLE ONLY public.client_keys
+    ADD CONSTRAINT client_keys_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.client_keys
+    ADD CONSTRAINT client_keys_key_id_key UNIQUE (key_id);
@@ -0,0 +1,40 @@
+--
+-- Name: policies; Type: TABLE; Schema: public; Owner: -
+--
+-- Grants a client, identified by the name of its client keys, a set of capabilities on
+-- the secret names (or vault connection public IDs) matching `pattern`, where `*`
+-- matches any sequence of characters.
+--
+
+CREATE TABLE public.policies (
+    id integer NOT NULL,
+    client_name text NOT NULL,
+    capabilities text[] NOT NULL,
+    pattern text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT policies_capabilities_check CHECK (
+        capabilities <@ ARRAY['read', 'create', 'create-version', 'manage-connections', 'admin']::text[]
+    )
+);
+
+CREATE SEQUENCE public.policies_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.policies_id_seq OWNED BY public.policies.id;
+
+ALTER TABLE ONLY public.policies ALTER COLUMN id SET DEFAULT nextval('public.policies_id_seq'::regclass);
+
+ALTER TABLE ONLY public.policies
+    ADD CONSTRAINT policies_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: idx_policies_client_name; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX idx_policies_client_name ON public.policies USING btree (client_name);
@@ -0,0 +1,17 @@
+--
+-- Name: request_nonces; Type: TABLE; Schema: public; Owner: -
+--
+-- Nonces of accepted signed requests, shared between replicas to reject replays. Rows
+-- are pruned once their request timestamp is outside the recv window.
+--
+
+CREATE TABLE public.request_nonces (
+    client_name text NOT NULL,
+    nonce text NOT NULL,
+    expires_at timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY public.request_nonces
+    ADD CONSTRAINT request_nonces_pkey PRIMARY KEY (client_name, nonce);
+
+CREATE INDEX request_nonces_expires_at_idx ON public.request_nonces USING btree (expires_at);
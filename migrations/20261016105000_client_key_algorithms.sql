@@ -0,0 +1,14 @@
+--
+-- Signature algorithm of each client key, determined from the key when it is
+-- registered. Existing keys are all SEC1 P-256 keys.
+--
+
+ALTER TABLE public.client_keys
+    ADD COLUMN algorithm text DEFAULT 'ecdsa-p256' NOT NULL;
+
+ALTER TABLE public.client_keys
+    ALTER COLUMN algorithm DROP DEFAULT;
+
+ALTER TABLE ONLY public.client_keys
+    ADD CONSTRAINT client_keys_algorithm_check
+    CHECK (algorithm IN ('ecdsa-p256', 'ecdsa-p384', 'ed25519'));
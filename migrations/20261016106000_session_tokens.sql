@@ -0,0 +1,48 @@
+--
+-- Name: session_tokens; Type: TABLE; Schema: public; Owner: -
+--
+-- Short-lived opaque tokens issued by `POST /v1/auth/login` to clients that signed the
+-- login request. Only the SHA-256 hash of each token is stored. `capabilities`, when
+-- set, restricts the token to a subset of what the client's policies grant.
+--
+
+CREATE TABLE public.session_tokens (
+    id integer NOT NULL,
+    token_hash text NOT NULL,
+    client_name text NOT NULL,
+    key_id text,
+    is_root boolean DEFAULT false NOT NULL,
+    capabilities text[],
+    expires_at timestamp with time zone NOT NULL,
+    revoked boolean DEFAULT false NOT NULL,
+    revoked_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT session_tokens_capabilities_check CHECK (
+        capabilities <@ ARRAY['read', 'create', 'create-version', 'manage-connections', 'admin']::text[]
+    )
+);
+
+CREATE SEQUENCE public.session_tokens_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.session_tokens_id_seq OWNED BY public.session_tokens.id;
+
+ALTER TABLE ONLY public.session_tokens ALTER COLUMN id SET DEFAULT nextval('public.session_tokens_id_seq'::regclass);
+
+ALTER TABLE ONLY public.session_tokens
+    ADD CONSTRAINT session_tokens_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.session_tokens
+    ADD CONSTRAINT session_tokens_token_hash_key UNIQUE (token_hash);
+
+
+--
+-- Name: idx_session_tokens_key_id; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX idx_session_tokens_key_id ON public.session_tokens USING btree (key_id);
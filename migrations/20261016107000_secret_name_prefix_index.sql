@@ -0,0 +1,8 @@
+--
+-- Name: idx_secrets_name_pattern; Type: INDEX; Schema: public; Owner: -
+--
+-- Serves the `name LIKE 'prefix%'` scans of folder listings and bulk operations, which
+-- the unique index on `name` cannot under non-C collations.
+--
+
+CREATE INDEX idx_secrets_name_pattern ON public.secrets USING btree (name text_pattern_ops);
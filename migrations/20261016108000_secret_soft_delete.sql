@@ -0,0 +1,24 @@
+--
+-- Name: secrets deleted_at; Type: COLUMN; Schema: public; Owner: -
+--
+-- Soft-deleted secrets (and versions, through the existing `deleted` and `deleted_at`
+-- columns) are hidden from reads and can be restored until the purger hard-deletes them
+-- once the recovery window has passed.
+--
+
+ALTER TABLE ONLY public.secrets
+    ADD COLUMN deleted_at timestamp with time zone;
+
+
+--
+-- Name: idx_secrets_deleted_at; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX idx_secrets_deleted_at ON public.secrets USING btree (deleted_at) WHERE (deleted_at IS NOT NULL);
+
+
+--
+-- Name: idx_secret_versions_deleted_at; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX idx_secret_versions_deleted_at ON public.secret_versions USING btree (deleted_at) WHERE deleted;
@@ -1,29 +1,193 @@
+use crate::crypto::Algorithm;
+use crate::keyring;
+use crate::rate_limit::RateLimit;
 use std::env;
+use std::fs;
+use std::str::FromStr;
 use std::sync::OnceLock;
+use zeroize::Zeroizing;
 
 static APP_CONFIG: OnceLock<AppConfig> = OnceLock::new();
 
 #[derive(Debug)]
 pub struct AppConfig {
     pub database_url: String,
-    pub auth_public_key: String,
+    pub auth_public_key: Option<String>,
     pub port: u16,
+    pub tls_cert_file: Option<String>,
+    pub tls_key_file: Option<String>,
+    pub tls_client_ca_file: Option<String>,
+    pub tls_client_auth_required: bool,
+    pub tls_reload_interval_secs: u64,
+    pub aws_kms_enabled: bool,
+    pub local_master_key: Option<Zeroizing<String>>,
+    pub shamir_seal_enabled: bool,
+    pub fingerprint_key: Option<Zeroizing<String>>,
+    pub data_encryption_algorithm: Algorithm,
+    pub replay_cache_shared: bool,
+    pub rate_limit_reads: RateLimit,
+    pub rate_limit_writes: RateLimit,
+    pub rate_limit_connection_admin: RateLimit,
+    pub rate_limit_ips: RateLimit,
+    pub client_max_concurrent_requests: usize,
+    pub readiness_cache_secs: u64,
+    pub readiness_check_providers: bool,
+    pub jwt_issuer: Option<String>,
+    pub jwt_audience: Option<String>,
+    pub jwt_jwks_url: Option<String>,
+    pub jwt_jwks_file: Option<String>,
+    pub jwt_allowed_namespaces: Vec<String>,
+    pub jwt_jwks_refresh_secs: u64,
+    pub kek_bootstrap_backend: String,
+    pub kek_bootstrap_key_ref: Option<String>,
+    pub kek_rotation_interval_secs: u64,
+    pub kek_rotation_batch_size: i64,
+    pub secret_recovery_window_secs: i64,
+    pub secret_purge_interval_secs: u64,
+    pub secret_purge_batch_size: i64,
+    pub dek_cache_capacity: usize,
+    pub dek_cache_ttl_secs: u64,
+    pub require_encryption_context: bool,
+    pub encryption_context_backfill: bool,
+    pub fingerprint_backfill: bool,
+    pub backfill_batch_size: i64,
 }
 
 impl AppConfig {
     pub fn load() -> Result<(), String> {
         let database_url = env::var("DB_URI").map_err(|_| "DB_URI must be set".to_string())?;
-        let auth_public_key =
-            env::var("AUTH_PUBLIC_KEY").map_err(|_| "AUTH_PUBLIC_KEY must be set".to_string())?;
+        let auth_public_key = env::var("AUTH_PUBLIC_KEY").ok();
         let port = env::var("PORT")
             .map_err(|_| "PORT must be set".to_string())?
             .parse::<u16>()
             .map_err(|_| "PORT must be a valid u16".to_string())?;
+        let tls_cert_file = env::var("TLS_CERT_FILE").ok();
+        let tls_key_file = env::var("TLS_KEY_FILE").ok();
+        if tls_cert_file.is_some() != tls_key_file.is_some() {
+            return Err("TLS_CERT_FILE and TLS_KEY_FILE must be set together".to_string());
+        }
+        let tls_client_ca_file = env::var("TLS_CLIENT_CA_FILE").ok();
+        let tls_client_auth_required = parse_env_or("TLS_CLIENT_AUTH_REQUIRED", false)?;
+        if tls_client_auth_required && tls_client_ca_file.is_none() {
+            return Err("TLS_CLIENT_AUTH_REQUIRED requires TLS_CLIENT_CA_FILE".to_string());
+        }
+        let tls_reload_interval_secs = parse_env_or("TLS_RELOAD_INTERVAL_SECS", 300)?;
+        let aws_kms_enabled = parse_env_or("AWS_KMS_ENABLED", true)?;
+        let local_master_key = load_secret_from_env_or_file("LOCAL_MASTER_KEY")?;
+        let shamir_seal_enabled = parse_env_or("SHAMIR_SEAL_ENABLED", false)?;
+        let fingerprint_key = load_secret_from_env_or_file("FINGERPRINT_KEY")?;
+        if fingerprint_key.is_none() && !parse_env_or("ALLOW_UNKEYED_FINGERPRINTS", false)? {
+            return Err(
+                "FINGERPRINT_KEY or FINGERPRINT_KEY_FILE must be set, unless ALLOW_UNKEYED_FINGERPRINTS is true"
+                    .to_string(),
+            );
+        }
+        let replay_cache_shared = parse_env_or("REPLAY_CACHE_SHARED", false)?;
+        let rate_limit_reads = parse_rate_limit("RATE_LIMIT_READS", 50.0, 100.0)?;
+        let rate_limit_writes = parse_rate_limit("RATE_LIMIT_WRITES", 10.0, 20.0)?;
+        let rate_limit_connection_admin =
+            parse_rate_limit("RATE_LIMIT_CONNECTION_ADMIN", 2.0, 5.0)?;
+        let rate_limit_ips = parse_rate_limit("RATE_LIMIT_IPS", 100.0, 200.0)?;
+        let client_max_concurrent_requests = parse_env_or("CLIENT_MAX_CONCURRENT_REQUESTS", 16)?;
+        let readiness_cache_secs = parse_env_or("READINESS_CACHE_SECS", 30)?;
+        let readiness_check_providers = parse_env_or("READINESS_CHECK_PROVIDERS", false)?;
+        let jwt_issuer = env::var("JWT_ISSUER").ok();
+        let jwt_audience = env::var("JWT_AUDIENCE").ok();
+        let jwt_jwks_url = env::var("JWT_JWKS_URL").ok();
+        let jwt_jwks_file = env::var("JWT_JWKS_FILE").ok();
+        if jwt_issuer.is_some() {
+            if jwt_audience.is_none() {
+                return Err("JWT_AUDIENCE must be set when JWT_ISSUER is set".to_string());
+            }
+            if jwt_jwks_url.is_some() == jwt_jwks_file.is_some() {
+                return Err(
+                    "Exactly one of JWT_JWKS_URL or JWT_JWKS_FILE must be set when JWT_ISSUER is set"
+                        .to_string(),
+                );
+            }
+        }
+        let jwt_allowed_namespaces = env::var("JWT_ALLOWED_NAMESPACES")
+            .map(|namespaces| {
+                namespaces
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|namespace| !namespace.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let jwt_jwks_refresh_secs = parse_env_or("JWT_JWKS_REFRESH_SECS", 300)?;
+        if jwt_jwks_refresh_secs == 0 {
+            return Err("JWT_JWKS_REFRESH_SECS must be greater than 0".to_string());
+        }
+        let kek_bootstrap_backend =
+            env::var("KEK_BOOTSTRAP_BACKEND").unwrap_or_else(|_| "aws_kms".to_string());
+        let kek_bootstrap_key_ref = env::var("KEK_BOOTSTRAP_KEY_REF").ok();
+        if kek_bootstrap_key_ref.is_some() && kek_bootstrap_backend == keyring::shamir::BACKEND_NAME
+        {
+            return Err(format!(
+                "KEK_BOOTSTRAP_BACKEND cannot be {}, as the vault is sealed at startup",
+                keyring::shamir::BACKEND_NAME
+            ));
+        }
+        let data_encryption_algorithm =
+            parse_env_or("DATA_ENCRYPTION_ALGORITHM", Algorithm::Aes256Gcm)?;
+        let kek_rotation_interval_secs = parse_env_or("KEK_ROTATION_INTERVAL_SECS", 300)?;
+        let kek_rotation_batch_size = parse_env_or("KEK_ROTATION_BATCH_SIZE", 100)?;
+        let secret_recovery_window_secs = parse_env_or("SECRET_RECOVERY_WINDOW_SECS", 2_592_000)?;
+        let secret_purge_interval_secs = parse_env_or("SECRET_PURGE_INTERVAL_SECS", 3600)?;
+        let secret_purge_batch_size = parse_env_or("SECRET_PURGE_BATCH_SIZE", 100)?;
+        let dek_cache_capacity = parse_env_or("DEK_CACHE_CAPACITY", 1000)?;
+        let dek_cache_ttl_secs = parse_env_or("DEK_CACHE_TTL_SECS", 300)?;
+        let require_encryption_context = parse_env_or("REQUIRE_ENCRYPTION_CONTEXT", false)?;
+        let encryption_context_backfill = parse_env_or("ENCRYPTION_CONTEXT_BACKFILL", false)?;
+        let fingerprint_backfill = parse_env_or("FINGERPRINT_BACKFILL", false)?;
+        if fingerprint_backfill && fingerprint_key.is_none() {
+            return Err("FINGERPRINT_BACKFILL requires FINGERPRINT_KEY".to_string());
+        }
+        let backfill_batch_size = parse_env_or("BACKFILL_BATCH_SIZE", 100)?;
 
         let config = AppConfig {
             database_url,
             auth_public_key,
             port,
+            tls_cert_file,
+            tls_key_file,
+            tls_client_ca_file,
+            tls_client_auth_required,
+            tls_reload_interval_secs,
+            aws_kms_enabled,
+            local_master_key,
+            shamir_seal_enabled,
+            fingerprint_key,
+            data_encryption_algorithm,
+            replay_cache_shared,
+            rate_limit_reads,
+            rate_limit_writes,
+            rate_limit_connection_admin,
+            rate_limit_ips,
+            client_max_concurrent_requests,
+            readiness_cache_secs,
+            readiness_check_providers,
+            jwt_issuer,
+            jwt_audience,
+            jwt_jwks_url,
+            jwt_jwks_file,
+            jwt_allowed_namespaces,
+            jwt_jwks_refresh_secs,
+            kek_bootstrap_backend,
+            kek_bootstrap_key_ref,
+            kek_rotation_interval_secs,
+            kek_rotation_batch_size,
+            secret_recovery_window_secs,
+            secret_purge_interval_secs,
+            secret_purge_batch_size,
+            dek_cache_capacity,
+            dek_cache_ttl_secs,
+            require_encryption_context,
+            encryption_context_backfill,
+            fingerprint_backfill,
+            backfill_batch_size,
         };
 
         if APP_CONFIG.set(config).is_err() {
@@ -37,3 +201,47 @@ impl AppConfig {
         APP_CONFIG.get().expect("Configuration has not been loaded")
     }
 }
+
+/// Parses `{name}` if it is set, falling back to `default` otherwise.
+fn parse_env_or<T: FromStr>(name: &str, default: T) -> Result<T, String> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse::<T>()
+            .map_err(|_| format!("{} has an invalid value", name)),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Parses `{prefix}_PER_SEC` and `{prefix}_BURST`, falling back to the defaults.
+fn parse_rate_limit(prefix: &str, per_sec: f64, burst: f64) -> Result<RateLimit, String> {
+    let per_sec: f64 = parse_env_or(&format!("{}_PER_SEC", prefix), per_sec)?;
+    let burst: f64 = parse_env_or(&format!("{}_BURST", prefix), burst)?;
+    if !per_sec.is_finite() || !burst.is_finite() {
+        return Err(format!(
+            "{}_PER_SEC and {}_BURST must be finite numbers",
+            prefix, prefix
+        ));
+    }
+    if per_sec < 0.0 || (per_sec > 0.0 && burst < 1.0) {
+        return Err(format!(
+            "{}_PER_SEC must not be negative and {}_BURST must be at least 1",
+            prefix, prefix
+        ));
+    }
+    Ok(RateLimit { per_sec, burst })
+}
+
+/// Reads a secret value from `{name}` or, if unset, from the file named by `{name}_FILE`.
+fn load_secret_from_env_or_file(name: &str) -> Result<Option<Zeroizing<String>>, String> {
+    if let Ok(value) = env::var(name) {
+        return Ok(Some(Zeroizing::new(value)));
+    }
+
+    let file_var = format!("{}_FILE", name);
+    match env::var(&file_var) {
+        Ok(path) => fs::read_to_string(&path)
+            .map(|value| Some(Zeroizing::new(value.trim().to_string())))
+            .map_err(|e| format!("Failed to read {} from {}: {}", file_var, path, e)),
+        Err(_) => Ok(None),
+    }
+}
@@ -1,123 +1,551 @@
 use crate::errors::AppError;
+use crate::keyring::Keyring;
+use crate::keyring::cache::UnwrappedDek;
 use crate::repositories::{dek::DekRepository, kek::KekRepository};
 use aes_gcm::{
-    Aes256Gcm, Nonce,
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm,
+    aead::{Aead, AeadCore, KeyInit, Nonce, OsRng, Payload},
 };
-use aws_sdk_kms::Client as KmsClient;
-use aws_sdk_kms::primitives::Blob;
+use chacha20poly1305::XChaCha20Poly1305;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::{PgPool, Postgres, Transaction};
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 
 pub struct EncryptedPayload {
     pub dek_id: i32,
-    pub encrypted_blob: String,
-    pub sha256sum: String,
+    pub encrypted_blob: Vec<u8>,
+    pub fingerprint: String,
 }
 
-const NONCE_SIZE: usize = 12; // AES-GCM standard nonce size
+/// Identifies the record a ciphertext belongs to.
+///
+/// The context is bound to the payload as AES-GCM associated data and to its DEK as the
+/// KMS encryption context, so a ciphertext moved to another row fails to decrypt.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct EncryptionContext(BTreeMap<String, String>);
+
+impl EncryptionContext {
+    pub fn secret_version(name: &str, version_tag: &str) -> Self {
+        Self(BTreeMap::from([
+            ("table".to_string(), "secret_versions".to_string()),
+            ("secret".to_string(), name.to_string()),
+            ("version".to_string(), version_tag.to_string()),
+        ]))
+    }
+
+    pub fn vault_connection(public_id: &str) -> Self {
+        Self(BTreeMap::from([
+            ("table".to_string(), "vault_connections".to_string()),
+            ("public_id".to_string(), public_id.to_string()),
+        ]))
+    }
+
+    /// Context for the test data keys generated when verifying a KEK.
+    pub fn kek_verification() -> Self {
+        Self(BTreeMap::from([(
+            "purpose".to_string(),
+            "kek_verification".to_string(),
+        )]))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Canonical encoding used as associated data: the context as JSON with sorted keys.
+    pub fn aad(&self) -> Vec<u8> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        serde_json::to_vec(&self.0).unwrap_or_default()
+    }
+
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.0.clone().into_iter().collect()
+    }
+}
+
+/// Data encryption algorithms, identified in the ciphertext envelope header and recorded
+/// per DEK in `data_encryption_keys.algo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Aes256Gcm,
+    /// XChaCha20-Poly1305, whose 192-bit nonces are safe to generate at random for far
+    /// more messages per key than AES-GCM's 96-bit nonces.
+    XChaCha20Poly1305,
+}
+
+impl Algorithm {
+    fn id(self) -> u8 {
+        match self {
+            Algorithm::Aes256Gcm => 1,
+            Algorithm::XChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, AppError> {
+        match id {
+            1 => Ok(Algorithm::Aes256Gcm),
+            2 => Ok(Algorithm::XChaCha20Poly1305),
+            _ => Err(AppError::CryptoError(format!(
+                "Unknown envelope algorithm id: {}",
+                id
+            ))),
+        }
+    }
+
+    /// The name recorded in `data_encryption_keys.algo`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Aes256Gcm => "AES-256-GCM",
+            Algorithm::XChaCha20Poly1305 => "XCHACHA20-POLY1305",
+        }
+    }
+
+    fn nonce_size(self) -> usize {
+        match self {
+            Algorithm::Aes256Gcm => 12,
+            Algorithm::XChaCha20Poly1305 => 24,
+        }
+    }
+
+    fn seal(self, key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, AppError> {
+        match self {
+            Algorithm::Aes256Gcm => seal_with::<Aes256Gcm>(key, plaintext, aad),
+            Algorithm::XChaCha20Poly1305 => seal_with::<XChaCha20Poly1305>(key, plaintext, aad),
+        }
+    }
+
+    fn open(self, key: &[u8], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, AppError> {
+        if sealed.len() < self.nonce_size() {
+            return Err(AppError::CryptoError(
+                "Invalid encrypted data format.".to_string(),
+            ));
+        }
+        let (nonce_bytes, ciphertext) = sealed.split_at(self.nonce_size());
+
+        match self {
+            Algorithm::Aes256Gcm => open_with::<Aes256Gcm>(key, nonce_bytes, ciphertext, aad),
+            Algorithm::XChaCha20Poly1305 => {
+                open_with::<XChaCha20Poly1305>(key, nonce_bytes, ciphertext, aad)
+            }
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = AppError;
+
+    /// Parses an algorithm from its `data_encryption_keys.algo` name, case-insensitively.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [Algorithm::Aes256Gcm, Algorithm::XChaCha20Poly1305]
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                AppError::CryptoError(format!("Unknown data encryption algorithm: {}", name))
+            })
+    }
+}
+
+/// Encrypts under a random nonce, returning `nonce || ciphertext`.
+fn seal_with<C: Aead + AeadCore + KeyInit>(
+    key: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, AppError> {
+    let cipher = C::new_from_slice(key)
+        .map_err(|e| AppError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+    let nonce = C::generate_nonce(&mut OsRng);
+    let encrypted_value = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|e| AppError::CryptoError(format!("Local encryption failed: {}", e)))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&encrypted_value);
+    Ok(sealed)
+}
+
+fn open_with<C: Aead + AeadCore + KeyInit>(
+    key: &[u8],
+    nonce_bytes: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, AppError> {
+    let cipher = C::new_from_slice(key).map_err(|e| {
+        AppError::CryptoError(format!("Failed to create cipher for decryption: {}", e))
+    })?;
+    cipher
+        .decrypt(
+            Nonce::<C>::from_slice(nonce_bytes),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|e| AppError::CryptoError(format!("Local decryption failed: {}", e)))
+}
+
+/// Current ciphertext envelope format:
+///
+/// | bytes | field                                  |
+/// |-------|----------------------------------------|
+/// | 1     | format version (`ENVELOPE_VERSION`)    |
+/// | 1     | algorithm id                           |
+/// | 4     | DEK id, big-endian                     |
+/// | n     | nonce, sized by the algorithm          |
+/// | rest  | ciphertext and authentication tag      |
+///
+/// The header is authenticated along with the encryption context. Payloads written before
+/// the envelope was introduced are ASCII hex of `nonce || ciphertext` and never start with
+/// the version byte.
+const ENVELOPE_VERSION: u8 = 1;
+const ENVELOPE_HEADER_SIZE: usize = 6;
+
+fn envelope_header(algorithm: Algorithm, dek_id: i32) -> Vec<u8> {
+    let mut header = Vec::with_capacity(ENVELOPE_HEADER_SIZE);
+    header.push(ENVELOPE_VERSION);
+    header.push(algorithm.id());
+    header.extend_from_slice(&(dek_id as u32).to_be_bytes());
+    header
+}
+
+fn envelope_aad(header: &[u8], context: &EncryptionContext) -> Vec<u8> {
+    let mut aad = header.to_vec();
+    aad.extend_from_slice(&context.aad());
+    aad
+}
 
 /// Encrypts a plaintext value using the envelope encryption strategy.
 pub async fn encrypt(
     tx: &mut Transaction<'_, Postgres>,
-    kms_client: &Arc<KmsClient>,
+    keyring: &Keyring,
     plaintext: &[u8],
+    context: &EncryptionContext,
 ) -> Result<EncryptedPayload, AppError> {
-    let kek = KekRepository::get_random_kek(tx).await?;
-
-    let data_key_response = kms_client
-        .generate_data_key()
-        .key_id(kek.kms_key)
-        .key_spec(aws_sdk_kms::types::DataKeySpec::Aes256)
-        .send()
-        .await
-        .map_err(|e| AppError::KmsError(format!("Failed to generate data key from KMS: {}", e)))?;
-
-    let plaintext_dek = data_key_response
-        .plaintext()
-        .ok_or_else(|| AppError::KmsError("KMS did not return a plaintext data key.".to_string()))?
-        .as_ref();
-    let encrypted_dek_blob = data_key_response.ciphertext_blob().ok_or_else(|| {
-        AppError::KmsError("KMS did not return a ciphertext blob for the data key.".to_string())
-    })?;
+    let algorithm = keyring.data_encryption_algorithm();
+    let kek = KekRepository::select_active_kek(tx).await?;
 
-    let cipher = Aes256Gcm::new_from_slice(plaintext_dek)
-        .map_err(|e| AppError::CryptoError(format!("Failed to create AES cipher: {}", e)))?;
-    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-    let encrypted_value = cipher
-        .encrypt(&nonce, plaintext)
-        .map_err(|e| AppError::CryptoError(format!("Local encryption failed: {}", e)))?;
+    let data_key = keyring
+        .wrapper(&kek.backend)?
+        .generate_data_key(&kek.kms_key, context)
+        .await?;
 
-    let mut combined_encrypted_value = nonce.to_vec();
-    combined_encrypted_value.extend_from_slice(&encrypted_value);
-    let encrypted_value_hex = hex::encode(combined_encrypted_value);
+    let new_dek =
+        DekRepository::create_dek(tx, kek.id, &data_key.wrapped, algorithm, context).await?;
 
-    let new_dek = DekRepository::create_dek(tx, kek.id, hex::encode(encrypted_dek_blob)).await?;
+    let mut envelope = envelope_header(algorithm, new_dek.id);
+    let sealed = algorithm.seal(
+        &data_key.plaintext,
+        plaintext,
+        &envelope_aad(&envelope, context),
+    )?;
+    envelope.extend_from_slice(&sealed);
 
     Ok(EncryptedPayload {
         dek_id: new_dek.id,
-        encrypted_blob: encrypted_value_hex,
-        sha256sum: sha256_hash(plaintext),
+        encrypted_blob: envelope,
+        fingerprint: fingerprint(keyring, plaintext),
     })
 }
 
 /// Decrypts an encrypted value using the envelope encryption strategy.
+///
+/// `context` must match the context the value was encrypted under. Values written before
+/// encryption contexts were introduced are decrypted without one, unless the keyring
+/// requires a context.
 pub async fn decrypt(
     pool: &PgPool,
-    kms_client: &Arc<KmsClient>,
+    keyring: &Keyring,
     dek_id: i32,
-    encrypted_value_hex: &str,
+    encrypted_value: &[u8],
+    context: &EncryptionContext,
 ) -> Result<Vec<u8>, AppError> {
-    let dek = DekRepository::get_dek_by_id(pool, dek_id).await?;
-    let kek = KekRepository::get_kek_by_id(pool, dek.kek_id).await?;
+    let dek = match keyring.dek_cache().get(dek_id) {
+        Some(dek) => dek,
+        None => {
+            let dek = unwrap_dek(pool, keyring, dek_id, context).await?;
+            keyring.dek_cache().insert(dek_id, dek.clone());
+            dek
+        }
+    };
 
-    let encrypted_dek_bytes = hex::decode(dek.encrypted_key).map_err(|e| {
-        AppError::CryptoError(format!("Failed to decode encrypted DEK from hex: {}", e))
-    })?;
-    let encrypted_dek_blob = Blob::new(encrypted_dek_bytes);
-
-    let decrypt_response = kms_client
-        .decrypt()
-        .key_id(kek.kms_key)
-        .ciphertext_blob(encrypted_dek_blob)
-        .send()
-        .await
-        .map_err(|e| AppError::KmsError(format!("Failed to decrypt data key with KMS: {}", e)))?;
-
-    let plaintext_dek = decrypt_response
-        .plaintext()
-        .ok_or_else(|| {
-            AppError::KmsError("KMS did not return a plaintext data key on decrypt.".to_string())
-        })?
-        .as_ref();
+    if !dek.context_bound && keyring.require_encryption_context() {
+        return Err(AppError::CryptoError(format!(
+            "DEK {} is not bound to an encryption context",
+            dek_id
+        )));
+    }
 
-    let combined_encrypted_value = hex::decode(encrypted_value_hex).map_err(|e| {
-        AppError::CryptoError(format!("Failed to decode encrypted value from hex: {}", e))
-    })?;
+    open_envelope(&dek, dek_id, encrypted_value, context)
+}
+
+/// Decrypts a payload, in the envelope format or the legacy one, with its unwrapped DEK.
+fn open_envelope(
+    dek: &UnwrappedDek,
+    dek_id: i32,
+    encrypted_value: &[u8],
+    context: &EncryptionContext,
+) -> Result<Vec<u8>, AppError> {
+    if encrypted_value.first() != Some(&ENVELOPE_VERSION) {
+        return decrypt_legacy(dek, encrypted_value, context);
+    }
 
-    if combined_encrypted_value.len() < NONCE_SIZE {
+    if encrypted_value.len() < ENVELOPE_HEADER_SIZE {
         return Err(AppError::CryptoError(
             "Invalid encrypted data format.".to_string(),
         ));
     }
-    let (nonce_bytes, ciphertext) = combined_encrypted_value.split_at(NONCE_SIZE);
-    let nonce = Nonce::from_slice(nonce_bytes);
+    let (header, sealed) = encrypted_value.split_at(ENVELOPE_HEADER_SIZE);
+    let algorithm = Algorithm::from_id(header[1])?;
+    if algorithm != dek.algorithm {
+        return Err(AppError::CryptoError(format!(
+            "Envelope algorithm {} does not match DEK {} algorithm {}",
+            algorithm.name(),
+            dek_id,
+            dek.algorithm.name()
+        )));
+    }
+    let envelope_dek_id = u32::from_be_bytes([header[2], header[3], header[4], header[5]]);
+    if envelope_dek_id != dek_id as u32 {
+        return Err(AppError::CryptoError(format!(
+            "Envelope references DEK {} but is stored with DEK {}",
+            envelope_dek_id, dek_id
+        )));
+    }
 
-    let cipher = Aes256Gcm::new_from_slice(plaintext_dek).map_err(|e| {
-        AppError::CryptoError(format!("Failed to create AES cipher for decryption: {}", e))
+    algorithm.open(&dek.key, sealed, &envelope_aad(header, context))
+}
+
+/// Decrypts a payload stored as hex of `nonce || ciphertext`, from before the envelope format.
+fn decrypt_legacy(
+    dek: &UnwrappedDek,
+    encrypted_value_hex: &[u8],
+    context: &EncryptionContext,
+) -> Result<Vec<u8>, AppError> {
+    let combined_encrypted_value = hex::decode(encrypted_value_hex).map_err(|e| {
+        AppError::CryptoError(format!("Failed to decode encrypted value from hex: {}", e))
     })?;
 
-    let decrypted_value = cipher
-        .decrypt(nonce, ciphertext)
-        .map_err(|e| AppError::CryptoError(format!("Local decryption failed: {}", e)))?;
+    let aad = if dek.context_bound {
+        context.aad()
+    } else {
+        Vec::new()
+    };
+    dek.algorithm
+        .open(&dek.key, &combined_encrypted_value, &aad)
+}
 
-    Ok(decrypted_value)
+async fn unwrap_dek(
+    pool: &PgPool,
+    keyring: &Keyring,
+    dek_id: i32,
+    context: &EncryptionContext,
+) -> Result<UnwrappedDek, AppError> {
+    let dek = DekRepository::get_dek_by_id(pool, dek_id).await?;
+    let algorithm = dek.algo.parse::<Algorithm>()?;
+    let kek = KekRepository::get_kek_by_id(pool, dek.kek_id).await?;
+
+    // Legacy DEKs were wrapped without an encryption context.
+    let context_bound = dek.encryption_context.is_some();
+    let wrapping_context = if context_bound {
+        context.clone()
+    } else {
+        EncryptionContext::default()
+    };
+
+    let key = keyring
+        .wrapper(&kek.backend)?
+        .unwrap_data_key(&kek.kms_key, &dek.encrypted_key, &wrapping_context)
+        .await?;
+
+    Ok(UnwrappedDek {
+        key,
+        algorithm,
+        context_bound,
+    })
 }
 
-pub fn sha256_hash(data: &[u8]) -> String {
+pub const FINGERPRINT_PREFIX: &str = "hmac-sha256:";
+
+/// Computes a keyed fingerprint of a plaintext for change detection.
+///
+/// Fingerprints are HMAC-SHA256 under the keyring's fingerprint key, so unlike a plain
+/// hash they cannot be used to brute-force low-entropy values offline. Without a
+/// fingerprint key, the unkeyed SHA-256 digest is written instead.
+pub fn fingerprint(keyring: &Keyring, data: &[u8]) -> String {
+    let Some(key) = keyring.fingerprint_key() else {
+        return sha256_hash(data);
+    };
+    let mut mac = fingerprint_mac(key);
+    mac.update(data);
+    format!(
+        "{}{}",
+        FINGERPRINT_PREFIX,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Whether a stored fingerprint was computed from `data`. Unkeyed SHA-256 fingerprints
+/// written before HMAC fingerprints were introduced are still recognised. HMAC
+/// fingerprints never match without a fingerprint key.
+pub fn fingerprint_matches(keyring: &Keyring, stored: &str, data: &[u8]) -> bool {
+    match stored.strip_prefix(FINGERPRINT_PREFIX) {
+        Some(stored_hex) => match (keyring.fingerprint_key(), hex::decode(stored_hex)) {
+            (Some(key), Ok(stored_mac)) => {
+                let mut mac = fingerprint_mac(key);
+                mac.update(data);
+                mac.verify_slice(&stored_mac).is_ok()
+            }
+            _ => false,
+        },
+        None => stored == sha256_hash(data),
+    }
+}
+
+fn fingerprint_mac(key: &[u8]) -> Hmac<Sha256> {
+    <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+fn sha256_hash(data: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(data);
     let result = hasher.finalize();
     hex::encode(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zeroize::Zeroizing;
+
+    const DEK_ID: i32 = 42;
+
+    fn dek(algorithm: Algorithm) -> UnwrappedDek {
+        UnwrappedDek {
+            key: Zeroizing::new(vec![0x24; 32]),
+            algorithm,
+            context_bound: true,
+        }
+    }
+
+    fn seal_envelope(
+        dek: &UnwrappedDek,
+        dek_id: i32,
+        plaintext: &[u8],
+        context: &EncryptionContext,
+    ) -> Vec<u8> {
+        let mut envelope = envelope_header(dek.algorithm, dek_id);
+        let sealed = dek
+            .algorithm
+            .seal(&dek.key, plaintext, &envelope_aad(&envelope, context))
+            .unwrap();
+        envelope.extend_from_slice(&sealed);
+        envelope
+    }
+
+    #[test]
+    fn aad_is_the_context_as_json_with_sorted_keys() {
+        let context = EncryptionContext::secret_version("payments/api-key", "v1");
+        assert_eq!(
+            context.aad(),
+            br#"{"secret":"payments/api-key","table":"secret_versions","version":"v1"}"#
+        );
+        assert!(EncryptionContext::default().aad().is_empty());
+    }
+
+    #[test]
+    fn aad_differs_between_records() {
+        let version = EncryptionContext::secret_version("payments/api-key", "v1");
+        assert_ne!(
+            version.aad(),
+            EncryptionContext::secret_version("payments/api-key", "v2").aad()
+        );
+        assert_ne!(
+            version.aad(),
+            EncryptionContext::secret_version("payments/api-key-v1", "").aad()
+        );
+        assert_ne!(
+            version.aad(),
+            EncryptionContext::vault_connection("payments/api-key").aad()
+        );
+    }
+
+    #[test]
+    fn envelope_round_trips_for_each_algorithm() {
+        let context = EncryptionContext::secret_version("payments/api-key", "v1");
+        for algorithm in [Algorithm::Aes256Gcm, Algorithm::XChaCha20Poly1305] {
+            let dek = dek(algorithm);
+            let envelope = seal_envelope(&dek, DEK_ID, b"hunter2", &context);
+            assert_eq!(envelope[0], ENVELOPE_VERSION);
+            assert_eq!(
+                open_envelope(&dek, DEK_ID, &envelope, &context).unwrap(),
+                b"hunter2"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_a_different_context() {
+        let dek = dek(Algorithm::Aes256Gcm);
+        let context = EncryptionContext::secret_version("payments/api-key", "v1");
+        let envelope = seal_envelope(&dek, DEK_ID, b"hunter2", &context);
+
+        let other = EncryptionContext::secret_version("payments/api-key", "v2");
+        assert!(open_envelope(&dek, DEK_ID, &envelope, &other).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_envelopes() {
+        let dek = dek(Algorithm::XChaCha20Poly1305);
+        let context = EncryptionContext::vault_connection("vc-1");
+        let envelope = seal_envelope(&dek, DEK_ID, b"hunter2", &context);
+
+        for len in [1, 3, ENVELOPE_HEADER_SIZE, ENVELOPE_HEADER_SIZE + 10] {
+            assert!(open_envelope(&dek, DEK_ID, &envelope[..len], &context).is_err());
+        }
+        let without_tag = &envelope[..envelope.len() - 1];
+        assert!(open_envelope(&dek, DEK_ID, without_tag, &context).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_algorithms_and_versions() {
+        let dek = dek(Algorithm::Aes256Gcm);
+        let context = EncryptionContext::vault_connection("vc-1");
+        let envelope = seal_envelope(&dek, DEK_ID, b"hunter2", &context);
+
+        let mut unknown_algorithm = envelope.clone();
+        unknown_algorithm[1] = 0xff;
+        assert!(open_envelope(&dek, DEK_ID, &unknown_algorithm, &context).is_err());
+
+        // Any other leading byte is read as a legacy hex payload, which this is not.
+        let mut unknown_version = envelope;
+        unknown_version[0] = ENVELOPE_VERSION + 1;
+        assert!(open_envelope(&dek, DEK_ID, &unknown_version, &context).is_err());
+    }
+
+    #[test]
+    fn rejects_envelopes_for_another_dek() {
+        let dek = dek(Algorithm::Aes256Gcm);
+        let context = EncryptionContext::vault_connection("vc-1");
+
+        let envelope = seal_envelope(&dek, DEK_ID + 1, b"hunter2", &context);
+        assert!(open_envelope(&dek, DEK_ID, &envelope, &context).is_err());
+
+        let other_algorithm = UnwrappedDek {
+            algorithm: Algorithm::XChaCha20Poly1305,
+            ..dek.clone()
+        };
+        let envelope = seal_envelope(&other_algorithm, DEK_ID, b"hunter2", &context);
+        assert!(open_envelope(&dek, DEK_ID, &envelope, &context).is_err());
+    }
+}
@@ -1,13 +1,14 @@
 use axum::extract::rejection::JsonRejection;
 use axum::{
     Json,
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use lockset_vault_provider::ProviderError;
 use serde::Serialize;
 use serde_json::json;
 use std::borrow::Cow;
+use std::time::Duration;
 use thiserror::Error;
 use tracing::error;
 use validator::ValidationErrors;
@@ -26,6 +27,9 @@ pub enum AppError {
     #[error("Item not found: {0}")]
     NotFoundErrorWithMessage(String),
 
+    #[error("Item destroyed")]
+    Destroyed,
+
     #[error("A conflict occurred")]
     Conflict,
 
@@ -41,6 +45,15 @@ pub enum AppError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Forbidden")]
+    Forbidden,
+
+    #[error("Vault is sealed")]
+    Sealed,
+
+    #[error("Rate limited")]
+    RateLimited(Duration),
+
     #[error(transparent)]
     JsonExtractionError(#[from] JsonRejection),
 
@@ -73,6 +86,19 @@ impl From<ProviderError> for AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        if let AppError::RateLimited(retry_after) = self {
+            // Whole seconds, rounded up so that retrying right on time succeeds.
+            let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                Json(json!({
+                    "error": "Too many requests, retry later",
+                })),
+            )
+                .into_response();
+        }
+
         let (status, error_message, data) = match self {
             AppError::DatabaseError(db_err) => {
                 error!("Database error: {}", db_err);
@@ -98,6 +124,11 @@ impl IntoResponse for AppError {
             AppError::NotFoundErrorWithMessage(message) => {
                 (StatusCode::NOT_FOUND, message.to_string(), None)
             }
+            AppError::Destroyed => (
+                StatusCode::GONE,
+                "The requested item has been destroyed".to_string(),
+                None,
+            ),
             AppError::Conflict => (
                 StatusCode::CONFLICT,
                 "A conflict occurred. The resource may already exist.".to_string(),
@@ -125,6 +156,17 @@ impl IntoResponse for AppError {
                 "You are not authorized to perform this action".to_string(),
                 None,
             ),
+            AppError::Forbidden => (
+                StatusCode::FORBIDDEN,
+                "You do not have permission to perform this action".to_string(),
+                None,
+            ),
+            AppError::Sealed => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "The vault is sealed".to_string(),
+                None,
+            ),
+            AppError::RateLimited(_) => unreachable!("handled above"),
             AppError::JsonExtractionError(rejection) => {
                 let message = rejection.body_text();
                 let status = rejection.status();
@@ -173,10 +215,10 @@ impl IntoResponse for AppError {
 
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
-        if let Some(e) = err.as_database_error() {
-            if e.is_unique_violation() {
-                return AppError::Conflict;
-            }
+        if let Some(e) = err.as_database_error()
+            && e.is_unique_violation()
+        {
+            return AppError::Conflict;
         }
         AppError::DatabaseError(err)
     }
@@ -1,2 +1,7 @@
+pub mod auth;
+pub mod client_keys;
 pub mod connections;
+pub mod keks;
+pub mod policies;
 pub mod secrets;
+pub mod sys;
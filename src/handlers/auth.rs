@@ -0,0 +1,42 @@
+use crate::{
+    errors::AppError,
+    models::{ClientIdentity, JsonPayload, LoginRequest, LoginResponse},
+    services::session_tokens::SessionTokenService,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+};
+use std::sync::Arc;
+
+pub struct AuthHandler;
+
+impl AuthHandler {
+    /// Exchange a signed request for a short-lived session token
+    pub async fn login(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        JsonPayload(payload): JsonPayload<LoginRequest>,
+    ) -> Result<(StatusCode, Json<LoginResponse>), AppError> {
+        let response = SessionTokenService::login(&state.db, &identity, payload).await?;
+        Ok((StatusCode::CREATED, Json(response)))
+    }
+
+    /// Revoke the session token the request was made with
+    pub async fn logout(
+        State(state): State<Arc<AppState>>,
+        headers: HeaderMap,
+    ) -> Result<StatusCode, AppError> {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| {
+                AppError::InvalidInput("Request was not made with a session token".to_string())
+            })?;
+        SessionTokenService::logout(&state.db, token.trim()).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    errors::AppError,
+    models::{ClientIdentity, ClientKeyResponse, CreateClientKeyRequest, JsonPayload},
+    services::{client_keys::ClientKeyService, policies::PolicyService},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use std::sync::Arc;
+
+pub struct ClientKeyHandler;
+
+impl ClientKeyHandler {
+    /// List all registered client keys
+    pub async fn list_client_keys(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+    ) -> Result<Json<Vec<ClientKeyResponse>>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = ClientKeyService::list_client_keys(&state.db).await?;
+        Ok(Json(response))
+    }
+
+    /// Register a new client verifying key
+    pub async fn create_client_key(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        JsonPayload(payload): JsonPayload<CreateClientKeyRequest>,
+    ) -> Result<(StatusCode, Json<ClientKeyResponse>), AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = ClientKeyService::register_client_key(&state.db, payload).await?;
+        Ok((StatusCode::CREATED, Json(response)))
+    }
+
+    /// Revoke a client key
+    pub async fn revoke_client_key(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(key_id): Path<String>,
+    ) -> Result<Json<ClientKeyResponse>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = ClientKeyService::revoke_client_key(&state.db, &key_id).await?;
+        Ok(Json(response))
+    }
+}
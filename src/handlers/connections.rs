@@ -1,13 +1,19 @@
 use crate::models::{
-    CreateVaultConnectionRequest, CreateVaultConnectionResponse, JsonPayload,
-    UpdateVaultConnectionRequest, UpdateVaultConnectionResponse, VaultConnectionResponse,
+    Capability, ClientIdentity, CreateVaultConnectionRequest, CreateVaultConnectionResponse,
+    JsonPayload, UpdateVaultConnectionRequest, UpdateVaultConnectionResponse,
+    VaultConnectionResponse,
 };
 use crate::{
-    errors::AppError, regex::get_public_id_regex, services::connections::ConnectionService,
+    errors::AppError,
+    regex::get_public_id_regex,
+    services::{
+        connections::ConnectionService,
+        policies::{PolicyService, connection_resource},
+    },
     state::AppState,
 };
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, State},
     http::StatusCode,
 };
@@ -19,8 +25,16 @@ impl ConnectionHandler {
     /// Create a new Vault Connection
     pub async fn create_vault_connection(
         State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
         JsonPayload(payload): JsonPayload<CreateVaultConnectionRequest>,
     ) -> Result<(StatusCode, Json<CreateVaultConnectionResponse>), AppError> {
+        PolicyService::authorize(
+            &state.db,
+            &identity,
+            Capability::ManageConnections,
+            &connection_resource(&payload.public_id),
+        )
+        .await?;
         let response = ConnectionService::create_vault_connection(&state, payload).await?;
         Ok((StatusCode::CREATED, Json(response)))
     }
@@ -28,6 +42,7 @@ impl ConnectionHandler {
     /// Update a Vault Connection
     pub async fn update_vault_connection(
         State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
         Path(public_id): Path<String>,
         JsonPayload(payload): JsonPayload<UpdateVaultConnectionRequest>,
     ) -> Result<Json<UpdateVaultConnectionResponse>, AppError> {
@@ -36,6 +51,13 @@ impl ConnectionHandler {
                 "Invalid public ID format".to_string(),
             ));
         }
+        PolicyService::authorize(
+            &state.db,
+            &identity,
+            Capability::ManageConnections,
+            &connection_resource(&public_id),
+        )
+        .await?;
         let response =
             ConnectionService::update_vault_connection(&state, &public_id, payload).await?;
         Ok(Json(response))
@@ -44,6 +66,7 @@ impl ConnectionHandler {
     /// Get a Vault Connection by its public ID
     pub async fn get_vault_connection(
         State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
         Path(public_id): Path<String>,
     ) -> Result<Json<VaultConnectionResponse>, AppError> {
         if !get_public_id_regex().is_match(&public_id) {
@@ -51,15 +74,22 @@ impl ConnectionHandler {
                 "Invalid public ID format".to_string(),
             ));
         }
+        PolicyService::authorize(
+            &state.db,
+            &identity,
+            Capability::ManageConnections,
+            &connection_resource(&public_id),
+        )
+        .await?;
         let response =
-            ConnectionService::get_vault_connection(&state.db, &state.kms_client, &public_id)
-                .await?;
+            ConnectionService::get_vault_connection(&state.db, &state.keyring, &public_id).await?;
         Ok(Json(response))
     }
 
     /// Delete a Vault Connection
     pub async fn delete_vault_connection(
         State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
         Path(public_id): Path<String>,
     ) -> Result<StatusCode, AppError> {
         if !get_public_id_regex().is_match(&public_id) {
@@ -67,6 +97,13 @@ impl ConnectionHandler {
                 "Invalid public ID format".to_string(),
             ));
         }
+        PolicyService::authorize(
+            &state.db,
+            &identity,
+            Capability::ManageConnections,
+            &connection_resource(&public_id),
+        )
+        .await?;
         let deleted = ConnectionService::delete_vault_connection(&state.db, &public_id).await?;
 
         if !deleted {
@@ -0,0 +1,82 @@
+use crate::{
+    errors::AppError,
+    models::{ClientIdentity, CreateKekRequest, JsonPayload, KekResponse, UpdateKekRequest},
+    services::{keks::KekService, policies::PolicyService},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use std::sync::Arc;
+
+pub struct KekHandler;
+
+impl KekHandler {
+    /// List all key encryption keys with their DEK usage counts
+    pub async fn list_keks(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+    ) -> Result<Json<Vec<KekResponse>>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = KekService::list_keks(&state.db).await?;
+        Ok(Json(response))
+    }
+
+    /// Register and verify a new key encryption key
+    pub async fn create_kek(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        JsonPayload(payload): JsonPayload<CreateKekRequest>,
+    ) -> Result<(StatusCode, Json<KekResponse>), AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = KekService::register_kek(&state, payload).await?;
+        Ok((StatusCode::CREATED, Json(response)))
+    }
+
+    /// Get a key encryption key by ID
+    pub async fn get_kek(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(id): Path<i32>,
+    ) -> Result<Json<KekResponse>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = KekService::get_kek(&state.db, id).await?;
+        Ok(Json(response))
+    }
+
+    /// Change the state or selection weight of a key encryption key
+    pub async fn update_kek(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(id): Path<i32>,
+        JsonPayload(payload): JsonPayload<UpdateKekRequest>,
+    ) -> Result<Json<KekResponse>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = KekService::update_kek(&state, id, payload).await?;
+        Ok(Json(response))
+    }
+
+    /// Delete a retired key encryption key
+    pub async fn delete_kek(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(id): Path<i32>,
+    ) -> Result<StatusCode, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        KekService::delete_kek(&state.db, id).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// Re-verify a key encryption key with a generate/unwrap round-trip
+    pub async fn verify_kek(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(id): Path<i32>,
+    ) -> Result<Json<KekResponse>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = KekService::verify_kek(&state, id).await?;
+        Ok(Json(response))
+    }
+}
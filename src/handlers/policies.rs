@@ -0,0 +1,53 @@
+use crate::{
+    errors::AppError,
+    models::{ClientIdentity, CreatePolicyRequest, JsonPayload, PolicyResponse},
+    services::policies::PolicyService,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use std::sync::Arc;
+
+pub struct PolicyHandler;
+
+impl PolicyHandler {
+    /// List all policies
+    pub async fn list_policies(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+    ) -> Result<Json<Vec<PolicyResponse>>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = PolicyService::list_policies(&state.db).await?;
+        Ok(Json(response))
+    }
+
+    /// Create a policy
+    pub async fn create_policy(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        JsonPayload(payload): JsonPayload<CreatePolicyRequest>,
+    ) -> Result<(StatusCode, Json<PolicyResponse>), AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = PolicyService::create_policy(&state.db, payload).await?;
+        Ok((StatusCode::CREATED, Json(response)))
+    }
+
+    /// Delete a policy
+    pub async fn delete_policy(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(id): Path<i32>,
+    ) -> Result<StatusCode, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let deleted = PolicyService::delete_policy(&state.db, id).await?;
+
+        if !deleted {
+            return Err(AppError::NotFoundError);
+        }
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
@@ -1,17 +1,26 @@
 use crate::{
     errors::AppError,
     models::{
-        CreateSecretRequest, CreateSecretResponse, CreateSecretVersionRequest,
-        CreateSecretVersionResponse, JsonPayload, SecretResponse,
+        Capability, ClientIdentity, CreateSecretRequest, CreateSecretResponse,
+        CreateSecretVersionRequest, CreateSecretVersionResponse, DeleteSecretQuery,
+        DeleteSecretResponse, DestroyFolderResponse, DestroySecretResponse, FolderResponse,
+        JsonPayload, ListSecretVersionsQuery, ListSecretVersionsResponse, ListSecretsQuery,
+        ListSecretsResponse, PromoteSecretVersionResponse, QueryPayload, RestoreSecretResponse,
+        SecretResponse,
     },
     regex::{get_secret_name_regex, get_version_tag_regex},
-    services::secrets::SecretService,
+    services::{
+        policies::{PolicyService, connection_resource},
+        secrets::SecretService,
+    },
     state::AppState,
+    validators::{VERSIONS_SEGMENT, validate_secret_path},
 };
 use axum::{
-    Json,
-    extract::{Path, State},
+    Extension, Json,
+    extract::{FromRequest, FromRequestParts, Path, Request, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
 };
 use std::sync::Arc;
 
@@ -21,6 +30,7 @@ impl SecretHandler {
     /// Register a new secret with its first version
     pub async fn create_secret(
         State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
         JsonPayload(payload): JsonPayload<CreateSecretRequest>,
     ) -> Result<(StatusCode, Json<CreateSecretResponse>), AppError> {
         if payload.vault_connection.is_some() && payload.value.is_some() {
@@ -34,56 +44,385 @@ impl SecretHandler {
                 "One of `vault_connection_id` or `value` must be present".to_string(),
             ));
         }
+        PolicyService::authorize(&state.db, &identity, Capability::Create, &payload.name).await?;
+        // A proxied secret reads through its connection, so binding one needs the same
+        // grant as administering it.
+        if let Some(public_id) = &payload.vault_connection {
+            PolicyService::authorize(
+                &state.db,
+                &identity,
+                Capability::ManageConnections,
+                &connection_resource(public_id),
+            )
+            .await?;
+        }
         let response = SecretService::create_secret_with_version(&state, payload).await?;
         Ok((StatusCode::CREATED, Json(response)))
     }
 
-    /// Get the current version of a secret by name
-    pub async fn get_secret(
+    /// List secret metadata, never values, with filters and cursor pagination
+    pub async fn list_secrets(
         State(state): State<Arc<AppState>>,
-        Path(name): Path<String>,
-    ) -> Result<Json<SecretResponse>, AppError> {
-        if !get_secret_name_regex().is_match(&name) {
-            return Err(AppError::InvalidInput(
-                "Invalid secret name format".to_string(),
-            ));
+        Extension(identity): Extension<ClientIdentity>,
+        QueryPayload(query): QueryPayload<ListSecretsQuery>,
+    ) -> Result<Json<ListSecretsResponse>, AppError> {
+        let response = SecretService::list_secrets(&state.db, &identity, query).await?;
+        Ok(Json(response))
+    }
+
+    /// Dispatch a `GET` under `/v1/secrets/`: the current version of a secret, its
+    /// version history with `versions`, or a specific version with `versions/{tag}`
+    pub async fn get_secret_path(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(path): Path<String>,
+        req: Request,
+    ) -> Result<Response, AppError> {
+        let path = SecretPath::parse(&path, false)?;
+        match (path.versions, path.tag, path.action) {
+            (false, None, None) => Ok(Self::get_secret(&state, &identity, &path.name)
+                .await?
+                .into_response()),
+            (true, None, None) => {
+                let (mut parts, _) = req.into_parts();
+                let QueryPayload(query) =
+                    QueryPayload::from_request_parts(&mut parts, &state).await?;
+                Ok(
+                    Self::list_secret_versions(&state, &identity, &path.name, query)
+                        .await?
+                        .into_response(),
+                )
+            }
+            (true, Some(tag), None) => {
+                Ok(Self::get_secret_version(&state, &identity, &path.name, tag)
+                    .await?
+                    .into_response())
+            }
+            _ => Err(AppError::NotFoundError),
         }
-        let response = SecretService::get_secret_current_version(&state, &name).await?;
+    }
+
+    /// Dispatch a `POST` under `/v1/secrets/`: `versions` to create a version,
+    /// `versions/{tag}/promote` to make a version current, `rollback` to go back to the
+    /// previous version, and `destroy` or `restore` on a secret or on `versions/{tag}`
+    pub async fn post_secret_path(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(path): Path<String>,
+        req: Request,
+    ) -> Result<Response, AppError> {
+        let path = SecretPath::parse(&path, true)?;
+        match (path.versions, path.tag, path.action) {
+            (true, None, None) => {
+                let JsonPayload(payload) = JsonPayload::from_request(req, &state).await?;
+                Ok(
+                    Self::create_secret_version(&state, &identity, &path.name, payload)
+                        .await?
+                        .into_response(),
+                )
+            }
+            (false, None, Some("destroy")) => {
+                Ok(Self::destroy_secret(&state, &identity, &path.name)
+                    .await?
+                    .into_response())
+            }
+            (true, Some(tag), Some("destroy")) => Ok(Self::destroy_secret_version(
+                &state, &identity, &path.name, tag,
+            )
+            .await?
+            .into_response()),
+            (false, None, Some("restore")) => {
+                Ok(Self::restore_secret(&state, &identity, &path.name)
+                    .await?
+                    .into_response())
+            }
+            (true, Some(tag), Some("restore")) => Ok(Self::restore_secret_version(
+                &state, &identity, &path.name, tag,
+            )
+            .await?
+            .into_response()),
+            (true, Some(tag), Some("promote")) => Ok(Self::promote_secret_version(
+                &state, &identity, &path.name, tag,
+            )
+            .await?
+            .into_response()),
+            (false, None, Some("rollback")) => {
+                Ok(Self::rollback_secret(&state, &identity, &path.name)
+                    .await?
+                    .into_response())
+            }
+            _ => Err(AppError::NotFoundError),
+        }
+    }
+
+    /// Dispatch a `DELETE` under `/v1/secrets/`: soft-delete a secret, or a version with
+    /// `versions/{tag}`
+    pub async fn delete_secret_path(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(path): Path<String>,
+        QueryPayload(query): QueryPayload<DeleteSecretQuery>,
+    ) -> Result<Json<DeleteSecretResponse>, AppError> {
+        let path = SecretPath::parse(&path, false)?;
+        match (path.versions, path.tag, path.action) {
+            (false, None, None) => Self::delete_secret(&state, &identity, &path.name).await,
+            (true, Some(tag), None) => {
+                Self::delete_secret_version(&state, &identity, &path.name, tag, query.force).await
+            }
+            _ => Err(AppError::NotFoundError),
+        }
+    }
+
+    /// List the secrets and subfolders at the root
+    pub async fn list_root_folder(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+    ) -> Result<Json<FolderResponse>, AppError> {
+        PolicyService::authorize(&state.db, &identity, Capability::Read, "").await?;
+        let response = SecretService::list_folder(&state.db, "").await?;
         Ok(Json(response))
     }
 
-    /// Create a new version for a first-class secret
-    pub async fn create_secret_version(
+    /// List the secrets and subfolders directly under a folder
+    pub async fn list_folder(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(path): Path<String>,
+    ) -> Result<Json<FolderResponse>, AppError> {
+        let prefix = folder_prefix(&path)?;
+        PolicyService::authorize(&state.db, &identity, Capability::Read, &prefix).await?;
+        let response = SecretService::list_folder(&state.db, &prefix).await?;
+        Ok(Json(response))
+    }
+
+    /// Dispatch a `POST` under `/v1/secret-folders/`: `destroy` to destroy every secret
+    /// under the folder
+    pub async fn post_folder_path(
         State(state): State<Arc<AppState>>,
-        Path(name): Path<String>,
-        JsonPayload(payload): JsonPayload<CreateSecretVersionRequest>,
+        Extension(identity): Extension<ClientIdentity>,
+        Path(path): Path<String>,
+    ) -> Result<Json<DestroyFolderResponse>, AppError> {
+        let Some((folder, "destroy")) = path.rsplit_once('/') else {
+            return Err(AppError::NotFoundError);
+        };
+        let prefix = folder_prefix(folder)?;
+        PolicyService::authorize(&state.db, &identity, Capability::Admin, &prefix).await?;
+        let response = SecretService::destroy_folder(&state, &identity, &prefix).await?;
+        Ok(Json(response))
+    }
+
+    /// Get the current version of a secret by name
+    async fn get_secret(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+    ) -> Result<Json<SecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Read, name).await?;
+        let response = SecretService::get_secret_current_version(state, name).await?;
+        Ok(Json(response))
+    }
+
+    /// List a secret's versions without decrypting them
+    async fn list_secret_versions(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        query: ListSecretVersionsQuery,
+    ) -> Result<Json<ListSecretVersionsResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Read, name).await?;
+        let response = SecretService::list_secret_versions(&state.db, name, query).await?;
+        Ok(Json(response))
+    }
+
+    /// Create a new version for a first-class secret
+    async fn create_secret_version(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        payload: CreateSecretVersionRequest,
     ) -> Result<(StatusCode, Json<CreateSecretVersionResponse>), AppError> {
-        if !get_secret_name_regex().is_match(&name) {
-            return Err(AppError::InvalidInput(
-                "Invalid secret name format".to_string(),
-            ));
-        }
-        let response = SecretService::create_secret_version(&state, &name, payload).await?;
+        PolicyService::authorize(&state.db, identity, Capability::CreateVersion, name).await?;
+        let response = SecretService::create_secret_version(state, name, payload).await?;
         Ok((StatusCode::CREATED, Json(response)))
     }
 
     /// Get a specific version of a secret
-    pub async fn get_secret_version(
-        State(state): State<Arc<AppState>>,
-        Path((name, tag)): Path<(String, String)>,
+    async fn get_secret_version(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        tag: &str,
     ) -> Result<Json<SecretResponse>, AppError> {
-        if !get_secret_name_regex().is_match(&name) {
+        PolicyService::authorize(&state.db, identity, Capability::Read, name).await?;
+        let response =
+            SecretService::get_secret_version(&state.db, &state.keyring, name, tag).await?;
+        Ok(Json(response))
+    }
+
+    /// Destroy a secret and all of its versions, making them unrecoverable
+    async fn destroy_secret(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+    ) -> Result<Json<DestroySecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Admin, name).await?;
+        let response = SecretService::destroy_secret(state, name).await?;
+        Ok(Json(response))
+    }
+
+    /// Make an existing version of a secret current
+    async fn promote_secret_version(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        tag: &str,
+    ) -> Result<Json<PromoteSecretVersionResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::CreateVersion, name).await?;
+        let response = SecretService::promote_secret_version(state, name, tag).await?;
+        Ok(Json(response))
+    }
+
+    /// Make the previous version of a secret current again
+    async fn rollback_secret(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+    ) -> Result<Json<PromoteSecretVersionResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::CreateVersion, name).await?;
+        let response = SecretService::rollback_secret(state, name).await?;
+        Ok(Json(response))
+    }
+
+    /// Soft-delete a secret, which can be restored within the recovery window
+    async fn delete_secret(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+    ) -> Result<Json<DeleteSecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Admin, name).await?;
+        let response = SecretService::delete_secret(state, name).await?;
+        Ok(Json(response))
+    }
+
+    /// Soft-delete a version of a secret, which can be restored within the recovery window
+    async fn delete_secret_version(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        tag: &str,
+        force: bool,
+    ) -> Result<Json<DeleteSecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Admin, name).await?;
+        let response = SecretService::delete_secret_version(state, name, tag, force).await?;
+        Ok(Json(response))
+    }
+
+    /// Restore a soft-deleted secret
+    async fn restore_secret(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+    ) -> Result<Json<RestoreSecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Admin, name).await?;
+        let response = SecretService::restore_secret(state, name).await?;
+        Ok(Json(response))
+    }
+
+    /// Restore a soft-deleted version of a secret
+    async fn restore_secret_version(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        tag: &str,
+    ) -> Result<Json<RestoreSecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Admin, name).await?;
+        let response = SecretService::restore_secret_version(state, name, tag).await?;
+        Ok(Json(response))
+    }
+
+    /// Destroy a single version of a secret, making it unrecoverable
+    async fn destroy_secret_version(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        name: &str,
+        tag: &str,
+    ) -> Result<Json<DestroySecretResponse>, AppError> {
+        PolicyService::authorize(&state.db, identity, Capability::Admin, name).await?;
+        let response = SecretService::destroy_secret_version(state, name, tag).await?;
+        Ok(Json(response))
+    }
+}
+
+/// A path under `/v1/secrets/`: a secret name, then optionally `versions` and a version
+/// tag, then optionally an action. Secret names cannot contain a `versions` segment, so
+/// the first one ends the name.
+struct SecretPath<'a> {
+    name: String,
+    versions: bool,
+    tag: Option<&'a str>,
+    action: Option<&'a str>,
+}
+
+impl<'a> SecretPath<'a> {
+    /// Parses `path`, where a secret name not followed by `versions` is followed by an
+    /// action if `with_action` is set.
+    fn parse(path: &'a str, with_action: bool) -> Result<Self, AppError> {
+        let segments: Vec<&str> = path.split('/').collect();
+
+        let (name, versions, tag, action) = match segments
+            .iter()
+            .position(|&segment| segment == VERSIONS_SEGMENT)
+        {
+            Some(index) => match segments[index + 1..] {
+                [] => (&segments[..index], true, None, None),
+                [tag] => (&segments[..index], true, Some(tag), None),
+                [tag, action] => (&segments[..index], true, Some(tag), Some(action)),
+                _ => return Err(AppError::NotFoundError),
+            },
+            None if with_action => match segments.split_last() {
+                Some((action, name)) => (name, false, None, Some(*action)),
+                None => return Err(AppError::NotFoundError),
+            },
+            None => (&segments[..], false, None, None),
+        };
+
+        let name = name.join("/");
+        if !is_valid_secret_name(&name) {
             return Err(AppError::InvalidInput(
                 "Invalid secret name format".to_string(),
             ));
         }
-        if !get_version_tag_regex().is_match(&tag) {
+        if let Some(tag) = tag
+            && !get_version_tag_regex().is_match(tag)
+        {
             return Err(AppError::InvalidInput(
                 "Invalid version tag format".to_string(),
             ));
         }
-        let response =
-            SecretService::get_secret_version(&state.db, &state.kms_client, &name, &tag).await?;
-        Ok(Json(response))
+
+        Ok(Self {
+            name,
+            versions,
+            tag,
+            action,
+        })
+    }
+}
+
+fn is_valid_secret_name(name: &str) -> bool {
+    name.len() <= 255
+        && get_secret_name_regex().is_match(name)
+        && validate_secret_path(name).is_ok()
+}
+
+/// Validates a folder path, with or without a trailing `/`, and returns it as the prefix
+/// of the secret names under it.
+fn folder_prefix(path: &str) -> Result<String, AppError> {
+    let folder = path.strip_suffix('/').unwrap_or(path);
+    if !is_valid_secret_name(folder) {
+        return Err(AppError::InvalidInput(
+            "Invalid folder name format".to_string(),
+        ));
     }
+    Ok(format!("{}/", folder))
 }
@@ -0,0 +1,55 @@
+use crate::{
+    errors::AppError,
+    models::{
+        ClientIdentity, InitSealRequest, InitSealResponse, JsonPayload, SealStatusResponse,
+        UnsealRequest,
+    },
+    services::{policies::PolicyService, seal::SealService},
+    state::AppState,
+};
+use axum::{Extension, Json, extract::State, http::StatusCode};
+use std::sync::Arc;
+
+pub struct SysHandler;
+
+impl SysHandler {
+    /// Get the seal status of the vault
+    pub async fn seal_status(
+        State(state): State<Arc<AppState>>,
+    ) -> Result<Json<SealStatusResponse>, AppError> {
+        let response = SealService::status(&state).await?;
+        Ok(Json(response))
+    }
+
+    /// Initialize the vault, returning the unseal shares
+    pub async fn init(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        JsonPayload(payload): JsonPayload<InitSealRequest>,
+    ) -> Result<(StatusCode, Json<InitSealResponse>), AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = SealService::init(&state, payload).await?;
+        Ok((StatusCode::CREATED, Json(response)))
+    }
+
+    /// Submit an unseal share
+    pub async fn unseal(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+        JsonPayload(payload): JsonPayload<UnsealRequest>,
+    ) -> Result<Json<SealStatusResponse>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = SealService::unseal(&state, payload).await?;
+        Ok(Json(response))
+    }
+
+    /// Seal the vault
+    pub async fn seal(
+        State(state): State<Arc<AppState>>,
+        Extension(identity): Extension<ClientIdentity>,
+    ) -> Result<Json<SealStatusResponse>, AppError> {
+        PolicyService::require_admin(&state.db, &identity).await?;
+        let response = SealService::seal_vault(&state).await?;
+        Ok(Json(response))
+    }
+}
@@ -0,0 +1,372 @@
+use crate::errors::AppError;
+use crate::models::ClientIdentity;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Minimum time between JWKS reloads triggered by tokens signed with an unknown key.
+const MIN_JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const JWKS_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the JSON Web Key Set that bearer tokens are verified against is loaded from.
+#[derive(Debug, Clone)]
+pub enum JwksSource {
+    File(PathBuf),
+    Url(String),
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    namespace: Option<String>,
+    #[serde(rename = "kubernetes.io")]
+    kubernetes: Option<KubernetesClaims>,
+}
+
+/// The claims Kubernetes adds to projected service account tokens.
+#[derive(Deserialize)]
+struct KubernetesClaims {
+    namespace: Option<String>,
+}
+
+impl Claims {
+    fn namespace(&self) -> Option<&str> {
+        self.kubernetes
+            .as_ref()
+            .and_then(|kubernetes| kubernetes.namespace.as_deref())
+            .or(self.namespace.as_deref())
+    }
+}
+
+/// Verifies `Authorization: Bearer` JWTs issued by a configured OIDC issuer, such as the
+/// Kubernetes service account issuer, against its JWKS.
+///
+/// The JWKS is reloaded every `refresh_interval` by [`JwtVerifier::spawn_refresher`], and
+/// sooner (at most every 30 seconds) when a token is signed with an unknown key ID, so
+/// issuer key rotation is picked up without a restart. A failed reload keeps the previous
+/// keys.
+pub struct JwtVerifier {
+    issuer: String,
+    audience: String,
+    allowed_namespaces: Vec<String>,
+    source: JwksSource,
+    refresh_interval: Duration,
+    http: reqwest::Client,
+    keys: RwLock<JwkSet>,
+    refreshed_at: Mutex<Instant>,
+}
+
+impl JwtVerifier {
+    /// Builds a verifier, loading the JWKS from `source`.
+    pub async fn new(
+        issuer: String,
+        audience: String,
+        allowed_namespaces: Vec<String>,
+        source: JwksSource,
+        refresh_interval: Duration,
+    ) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .timeout(JWKS_FETCH_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build JWKS client: {}", e))?;
+        let keys = load_jwks(&http, &source).await?;
+        info!(issuer = %issuer, keys = keys.keys.len(), "JWKS loaded");
+
+        Ok(Self {
+            issuer,
+            audience,
+            allowed_namespaces,
+            source,
+            refresh_interval,
+            http,
+            keys: RwLock::new(keys),
+            refreshed_at: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Spawn a background task that reloads the JWKS every `refresh_interval`, so that
+    /// requests never wait on a scheduled reload.
+    pub fn spawn_refresher(verifier: Arc<JwtVerifier>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(verifier.refresh_interval);
+            // The first tick completes immediately, and the keys were loaded at startup.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = verifier.refresh(MIN_JWKS_REFRESH_INTERVAL).await {
+                    warn!("Failed to reload JWKS: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Verifies a bearer token and maps its `sub` and namespace claims and audience to a
+    /// client identity.
+    ///
+    /// Tokens must be signed with an asymmetric algorithm by a key in the JWKS, name the
+    /// configured issuer and audience, and be unexpired. When namespaces are configured,
+    /// the token's Kubernetes namespace must be one of them.
+    pub async fn verify(&self, token: &str) -> Result<ClientIdentity, AppError> {
+        let header = decode_header(token).map_err(|e| rejected("malformed token", e))?;
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(rejected("symmetric algorithm", format!("{:?}", header.alg)));
+        }
+        let kid = header
+            .kid
+            .ok_or_else(|| rejected("missing key ID", "no `kid` header"))?;
+
+        let jwk = match self.find_key(&kid) {
+            Some(jwk) => jwk,
+            None => {
+                if let Err(e) = self.refresh(MIN_JWKS_REFRESH_INTERVAL).await {
+                    warn!("Failed to reload JWKS: {}", e);
+                }
+                self.find_key(&kid)
+                    .ok_or_else(|| rejected("unknown key ID", &kid))?
+            }
+        };
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| rejected("unusable JWK", e))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = decode::<Claims>(token, &key, &validation)
+            .map_err(|e| rejected("invalid token", e))?
+            .claims;
+
+        if !self.allowed_namespaces.is_empty()
+            && !claims
+                .namespace()
+                .is_some_and(|namespace| self.allowed_namespaces.iter().any(|n| n == namespace))
+        {
+            return Err(rejected(
+                "namespace not allowed",
+                claims.namespace().unwrap_or_default(),
+            ));
+        }
+
+        Ok(ClientIdentity::bearer_token(
+            &claims.sub,
+            claims.namespace(),
+            &self.audience,
+        ))
+    }
+
+    fn find_key(&self, kid: &str) -> Option<Jwk> {
+        self.keys.read().unwrap().find(kid).cloned()
+    }
+
+    /// Reloads the JWKS unless it was last (attempted to be) reloaded within `min_age`.
+    async fn refresh(&self, min_age: Duration) -> Result<(), String> {
+        let mut refreshed_at = self.refreshed_at.lock().await;
+        if refreshed_at.elapsed() < min_age {
+            return Ok(());
+        }
+        *refreshed_at = Instant::now();
+
+        let keys = load_jwks(&self.http, &self.source).await?;
+        info!(issuer = %self.issuer, keys = keys.keys.len(), "JWKS reloaded");
+        *self.keys.write().unwrap() = keys;
+        Ok(())
+    }
+}
+
+async fn load_jwks(http: &reqwest::Client, source: &JwksSource) -> Result<JwkSet, String> {
+    match source {
+        JwksSource::File(path) => {
+            let contents = tokio::fs::read(path)
+                .await
+                .map_err(|e| format!("Failed to read JWKS from {}: {}", path.display(), e))?;
+            serde_json::from_slice(&contents)
+                .map_err(|e| format!("Invalid JWKS in {}: {}", path.display(), e))
+        }
+        JwksSource::Url(url) => http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to fetch JWKS from {}: {}", url, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid JWKS from {}: {}", url, e)),
+    }
+}
+
+fn rejected(reason: &str, detail: impl std::fmt::Display) -> AppError {
+    warn!(reason, %detail, "bearer token rejected");
+    AppError::Unauthorized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use p256::elliptic_curve::sec1::ToEncodedPoint;
+    use p256::pkcs8::{EncodePrivateKey, LineEnding};
+    use serde_json::{Value, json};
+
+    const ISSUER: &str = "https://kubernetes.default.svc";
+    const AUDIENCE: &str = "lockset-vault";
+    const KID: &str = "test-key";
+
+    fn signing_key() -> p256::SecretKey {
+        p256::SecretKey::from_slice(&[3; 32]).unwrap()
+    }
+
+    /// Writes a JWKS holding the test key to a file unique to `name`.
+    fn jwks_file(name: &str) -> PathBuf {
+        let point = signing_key().public_key().to_encoded_point(false);
+        let jwks = json!({
+            "keys": [{
+                "kty": "EC",
+                "crv": "P-256",
+                "kid": KID,
+                "alg": "ES256",
+                "x": URL_SAFE_NO_PAD.encode(point.x().unwrap()),
+                "y": URL_SAFE_NO_PAD.encode(point.y().unwrap()),
+            }]
+        });
+        let path = std::env::temp_dir().join(format!(
+            "lockset-vault-jwks-{}-{}.json",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, jwks.to_string()).unwrap();
+        path
+    }
+
+    async fn verifier(name: &str, allowed_namespaces: &[&str]) -> JwtVerifier {
+        JwtVerifier::new(
+            ISSUER.to_string(),
+            AUDIENCE.to_string(),
+            allowed_namespaces.iter().map(|n| n.to_string()).collect(),
+            JwksSource::File(jwks_file(name)),
+            Duration::from_secs(3600),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn claims(overrides: Value) -> Value {
+        let mut claims = json!({
+            "iss": ISSUER,
+            "aud": AUDIENCE,
+            "sub": "system:serviceaccount:payments:api",
+            "exp": chrono::Utc::now().timestamp() + 600,
+            "kubernetes.io": { "namespace": "payments" },
+        });
+        for (claim, value) in overrides.as_object().unwrap() {
+            claims[claim] = value.clone();
+        }
+        claims
+    }
+
+    fn sign(claims: &Value) -> String {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(KID.to_string());
+        let pem = signing_key().to_pkcs8_pem(LineEnding::LF).unwrap();
+        encode(
+            &header,
+            claims,
+            &EncodingKey::from_ec_pem(pem.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn maps_claims_to_a_namespaced_identity() {
+        let verifier = verifier("maps", &[]).await;
+        let identity = verifier.verify(&sign(&claims(json!({})))).await.unwrap();
+
+        assert_eq!(identity.name, "jwt:system:serviceaccount:payments:api");
+        assert_eq!(identity.namespace.as_deref(), Some("payments"));
+        assert_eq!(identity.audience.as_deref(), Some(AUDIENCE));
+        assert_eq!(identity.auth_method, crate::models::AuthMethod::BearerToken);
+        assert!(!identity.is_root);
+    }
+
+    #[tokio::test]
+    async fn rejects_symmetric_algorithms() {
+        let verifier = verifier("hmac", &[]).await;
+        for algorithm in [Algorithm::HS256, Algorithm::HS384, Algorithm::HS512] {
+            let mut header = Header::new(algorithm);
+            header.kid = Some(KID.to_string());
+            let token = encode(
+                &header,
+                &claims(json!({})),
+                &EncodingKey::from_secret(b"shared secret"),
+            )
+            .unwrap();
+            assert!(matches!(
+                verifier.verify(&token).await,
+                Err(AppError::Unauthorized)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_the_wrong_issuer_or_audience() {
+        let verifier = verifier("issuer", &[]).await;
+        for overrides in [
+            json!({ "iss": "https://attacker.example" }),
+            json!({ "aud": "another-service" }),
+            json!({ "exp": chrono::Utc::now().timestamp() - 600 }),
+        ] {
+            let token = sign(&claims(overrides));
+            assert!(matches!(
+                verifier.verify(&token).await,
+                Err(AppError::Unauthorized)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_key_ids() {
+        let verifier = verifier("kid", &[]).await;
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some("rotated-away".to_string());
+        let pem = signing_key().to_pkcs8_pem(LineEnding::LF).unwrap();
+        let token = encode(
+            &header,
+            &claims(json!({})),
+            &EncodingKey::from_ec_pem(pem.as_bytes()).unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            verifier.verify(&token).await,
+            Err(AppError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn filters_by_namespace() {
+        let verifier = verifier("namespaces", &["payments", "billing"]).await;
+
+        assert!(verifier.verify(&sign(&claims(json!({})))).await.is_ok());
+
+        let plain_claim = claims(json!({ "kubernetes.io": null, "namespace": "billing" }));
+        assert!(verifier.verify(&sign(&plain_claim)).await.is_ok());
+
+        for overrides in [
+            json!({ "kubernetes.io": { "namespace": "default" } }),
+            json!({ "kubernetes.io": null }),
+        ] {
+            assert!(matches!(
+                verifier.verify(&sign(&claims(overrides))).await,
+                Err(AppError::Unauthorized)
+            ));
+        }
+    }
+}
@@ -0,0 +1,110 @@
+pub mod aws_kms;
+pub mod cache;
+pub mod local;
+pub mod shamir;
+
+use crate::crypto::{Algorithm, EncryptionContext};
+use crate::errors::AppError;
+use crate::keyring::cache::DekCache;
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+/// A freshly generated data encryption key, in plaintext and wrapped form.
+pub struct DataKey {
+    pub plaintext: Zeroizing<Vec<u8>>,
+    pub wrapped: Vec<u8>,
+}
+
+/// Defines the contract for a backend that wraps and unwraps data encryption keys
+/// under a key encryption key.
+///
+/// Every operation takes the encryption context of the payload the DEK protects; a key
+/// wrapped under one context must fail to unwrap under any other.
+#[async_trait::async_trait]
+pub trait KeyWrapper: Send + Sync {
+    /// Generates a new 256-bit data key wrapped under the key identified by `key_ref`.
+    async fn generate_data_key(
+        &self,
+        key_ref: &str,
+        context: &EncryptionContext,
+    ) -> Result<DataKey, AppError>;
+
+    /// Wraps an existing data key under the key identified by `key_ref`.
+    async fn wrap_data_key(
+        &self,
+        key_ref: &str,
+        plaintext: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Vec<u8>, AppError>;
+
+    /// Unwraps a data key previously wrapped under the key identified by `key_ref`.
+    async fn unwrap_data_key(
+        &self,
+        key_ref: &str,
+        wrapped_key: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Zeroizing<Vec<u8>>, AppError>;
+}
+
+/// The set of key wrapping backends available to this instance, keyed by the
+/// `backend` column of `key_encryption_keys`, along with the cache of unwrapped DEKs, the
+/// algorithm new payloads are encrypted with and the key used to fingerprint plaintexts.
+pub struct Keyring {
+    wrappers: HashMap<String, Box<dyn KeyWrapper>>,
+    dek_cache: DekCache,
+    data_encryption_algorithm: Algorithm,
+    fingerprint_key: Option<Zeroizing<Vec<u8>>>,
+    require_encryption_context: bool,
+}
+
+impl Keyring {
+    pub fn new(
+        dek_cache: DekCache,
+        data_encryption_algorithm: Algorithm,
+        fingerprint_key: Option<Zeroizing<Vec<u8>>>,
+        require_encryption_context: bool,
+    ) -> Self {
+        Self {
+            wrappers: HashMap::new(),
+            dek_cache,
+            data_encryption_algorithm,
+            fingerprint_key,
+            require_encryption_context,
+        }
+    }
+
+    pub fn register(&mut self, backend: &str, wrapper: Box<dyn KeyWrapper>) {
+        self.wrappers.insert(backend.to_string(), wrapper);
+    }
+
+    pub fn wrapper(&self, backend: &str) -> Result<&dyn KeyWrapper, AppError> {
+        self.wrappers
+            .get(backend)
+            .map(|wrapper| wrapper.as_ref())
+            .ok_or_else(|| {
+                AppError::KmsError(format!(
+                    "Key wrapping backend '{}' is not configured",
+                    backend
+                ))
+            })
+    }
+
+    pub fn dek_cache(&self) -> &DekCache {
+        &self.dek_cache
+    }
+
+    /// The algorithm recorded on, and used with, newly created DEKs.
+    pub fn data_encryption_algorithm(&self) -> Algorithm {
+        self.data_encryption_algorithm
+    }
+
+    /// The key for HMAC fingerprints, if one is configured.
+    pub fn fingerprint_key(&self) -> Option<&[u8]> {
+        self.fingerprint_key.as_deref().map(Vec::as_slice)
+    }
+
+    /// Whether payloads written without an encryption context must be rejected.
+    pub fn require_encryption_context(&self) -> bool {
+        self.require_encryption_context
+    }
+}
@@ -0,0 +1,112 @@
+use crate::crypto::EncryptionContext;
+use crate::errors::AppError;
+use crate::keyring::{DataKey, KeyWrapper};
+use aws_sdk_kms::Client as KmsClient;
+use aws_sdk_kms::primitives::Blob;
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+pub const BACKEND_NAME: &str = "aws_kms";
+
+/// Wraps data keys with AWS KMS; `key_ref` is a KMS key ID or ARN.
+pub struct AwsKmsKeyWrapper {
+    client: KmsClient,
+}
+
+impl AwsKmsKeyWrapper {
+    pub fn new(client: KmsClient) -> Self {
+        Self { client }
+    }
+}
+
+/// KMS rejects an empty encryption context map, so legacy keys send none at all.
+fn kms_encryption_context(context: &EncryptionContext) -> Option<HashMap<String, String>> {
+    if context.is_empty() {
+        None
+    } else {
+        Some(context.to_map())
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyWrapper for AwsKmsKeyWrapper {
+    async fn generate_data_key(
+        &self,
+        key_ref: &str,
+        context: &EncryptionContext,
+    ) -> Result<DataKey, AppError> {
+        let data_key_response = self
+            .client
+            .generate_data_key()
+            .key_id(key_ref)
+            .key_spec(aws_sdk_kms::types::DataKeySpec::Aes256)
+            .set_encryption_context(kms_encryption_context(context))
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::KmsError(format!("Failed to generate data key from KMS: {}", e))
+            })?;
+
+        let plaintext = data_key_response.plaintext().ok_or_else(|| {
+            AppError::KmsError("KMS did not return a plaintext data key.".to_string())
+        })?;
+        let wrapped = data_key_response.ciphertext_blob().ok_or_else(|| {
+            AppError::KmsError("KMS did not return a ciphertext blob for the data key.".to_string())
+        })?;
+
+        Ok(DataKey {
+            plaintext: Zeroizing::new(plaintext.as_ref().to_vec()),
+            wrapped: wrapped.as_ref().to_vec(),
+        })
+    }
+
+    async fn wrap_data_key(
+        &self,
+        key_ref: &str,
+        plaintext: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Vec<u8>, AppError> {
+        let encrypt_response = self
+            .client
+            .encrypt()
+            .key_id(key_ref)
+            .plaintext(Blob::new(plaintext))
+            .set_encryption_context(kms_encryption_context(context))
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::KmsError(format!("Failed to encrypt data key with KMS: {}", e))
+            })?;
+
+        let wrapped = encrypt_response.ciphertext_blob().ok_or_else(|| {
+            AppError::KmsError("KMS did not return a ciphertext blob on encrypt.".to_string())
+        })?;
+
+        Ok(wrapped.as_ref().to_vec())
+    }
+
+    async fn unwrap_data_key(
+        &self,
+        key_ref: &str,
+        wrapped_key: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Zeroizing<Vec<u8>>, AppError> {
+        let decrypt_response = self
+            .client
+            .decrypt()
+            .key_id(key_ref)
+            .ciphertext_blob(Blob::new(wrapped_key))
+            .set_encryption_context(kms_encryption_context(context))
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::KmsError(format!("Failed to decrypt data key with KMS: {}", e))
+            })?;
+
+        let plaintext = decrypt_response.plaintext().ok_or_else(|| {
+            AppError::KmsError("KMS did not return a plaintext data key on decrypt.".to_string())
+        })?;
+
+        Ok(Zeroizing::new(plaintext.as_ref().to_vec()))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::crypto::Algorithm;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// A DEK unwrapped by its key wrapping backend.
+#[derive(Clone)]
+pub struct UnwrappedDek {
+    pub key: Zeroizing<Vec<u8>>,
+    pub algorithm: Algorithm,
+    /// Whether the DEK and its payload are bound to an encryption context.
+    pub context_bound: bool,
+}
+
+struct CachedDek {
+    dek: UnwrappedDek,
+    inserted_at: Instant,
+}
+
+pub struct DekCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+}
+
+/// A bounded, TTL-limited cache of unwrapped DEKs keyed by `data_encryption_keys.id`.
+///
+/// Plaintext keys are held in `Zeroizing` buffers and wiped when evicted, expired or
+/// flushed. A capacity of zero disables the cache.
+pub struct DekCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: Mutex<HashMap<i32, CachedDek>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl DekCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, dek_id: i32) -> Option<UnwrappedDek> {
+        if self.capacity == 0 {
+            return None;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let dek = match entries.get(&dek_id) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => Some(entry.dek.clone()),
+            Some(_) => {
+                entries.remove(&dek_id);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if dek.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        dek
+    }
+
+    pub fn insert(&self, dek_id: i32, dek: UnwrappedDek) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity && !entries.contains_key(&dek_id) {
+            entries.retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+        }
+        if entries.len() >= self.capacity && !entries.contains_key(&dek_id) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(
+            dek_id,
+            CachedDek {
+                dek,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops a single cached key.
+    pub fn evict(&self, dek_id: i32) {
+        self.entries.lock().unwrap().remove(&dek_id);
+    }
+
+    /// Drops every cached key.
+    pub fn flush(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub fn stats(&self) -> DekCacheStats {
+        DekCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            size: self.entries.lock().unwrap().len(),
+        }
+    }
+}
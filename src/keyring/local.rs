@@ -0,0 +1,126 @@
+use crate::crypto::EncryptionContext;
+use crate::errors::AppError;
+use crate::keyring::{DataKey, KeyWrapper};
+use aes_gcm::{
+    Aes256Gcm, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+};
+use zeroize::Zeroizing;
+
+pub const BACKEND_NAME: &str = "local";
+
+pub const MASTER_KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+
+/// Wraps data keys with AES-256-GCM under a master key held in process memory.
+///
+/// The KEK's `key_ref` and the payload's encryption context are bound to every wrapped
+/// key as associated data, so a wrapped key can only be unwrapped through the KEK row
+/// and for the payload it was created for.
+pub struct LocalKeyWrapper {
+    master_key: Zeroizing<Vec<u8>>,
+}
+
+impl LocalKeyWrapper {
+    /// Builds a wrapper from a hex-encoded 256-bit master key.
+    pub fn from_hex(master_key_hex: &str) -> Result<Self, AppError> {
+        let master_key = Zeroizing::new(hex::decode(master_key_hex.trim()).map_err(|e| {
+            AppError::CryptoError(format!("Failed to decode local master key from hex: {}", e))
+        })?);
+
+        Self::new(master_key)
+    }
+
+    /// Builds a wrapper from a raw 256-bit master key.
+    pub fn new(master_key: Zeroizing<Vec<u8>>) -> Result<Self, AppError> {
+        if master_key.len() != MASTER_KEY_SIZE {
+            return Err(AppError::CryptoError(format!(
+                "Local master key must be {} bytes, got {}",
+                MASTER_KEY_SIZE,
+                master_key.len()
+            )));
+        }
+
+        Ok(Self { master_key })
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm, AppError> {
+        Aes256Gcm::new_from_slice(&self.master_key)
+            .map_err(|e| AppError::CryptoError(format!("Failed to create AES cipher: {}", e)))
+    }
+}
+
+fn wrapping_aad(key_ref: &str, context: &EncryptionContext) -> Vec<u8> {
+    let mut aad = key_ref.as_bytes().to_vec();
+    let context_aad = context.aad();
+    if !context_aad.is_empty() {
+        aad.push(b'\n');
+        aad.extend_from_slice(&context_aad);
+    }
+    aad
+}
+
+#[async_trait::async_trait]
+impl KeyWrapper for LocalKeyWrapper {
+    async fn generate_data_key(
+        &self,
+        key_ref: &str,
+        context: &EncryptionContext,
+    ) -> Result<DataKey, AppError> {
+        let plaintext = Zeroizing::new(Aes256Gcm::generate_key(&mut OsRng).to_vec());
+        let wrapped = self.wrap_data_key(key_ref, &plaintext, context).await?;
+
+        Ok(DataKey { plaintext, wrapped })
+    }
+
+    async fn wrap_data_key(
+        &self,
+        key_ref: &str,
+        plaintext: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Vec<u8>, AppError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped_key = self
+            .cipher()?
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &wrapping_aad(key_ref, context),
+                },
+            )
+            .map_err(|e| AppError::CryptoError(format!("Failed to wrap data key: {}", e)))?;
+
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&wrapped_key);
+
+        Ok(wrapped)
+    }
+
+    async fn unwrap_data_key(
+        &self,
+        key_ref: &str,
+        wrapped_key: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Zeroizing<Vec<u8>>, AppError> {
+        if wrapped_key.len() < NONCE_SIZE {
+            return Err(AppError::CryptoError(
+                "Invalid wrapped data key format.".to_string(),
+            ));
+        }
+        let (nonce_bytes, ciphertext) = wrapped_key.split_at(NONCE_SIZE);
+
+        let plaintext = self
+            .cipher()?
+            .decrypt(
+                Nonce::from_slice(nonce_bytes),
+                Payload {
+                    msg: ciphertext,
+                    aad: &wrapping_aad(key_ref, context),
+                },
+            )
+            .map_err(|e| AppError::CryptoError(format!("Failed to unwrap data key: {}", e)))?;
+
+        Ok(Zeroizing::new(plaintext))
+    }
+}
@@ -0,0 +1,132 @@
+use crate::crypto::EncryptionContext;
+use crate::errors::AppError;
+use crate::keyring::local::LocalKeyWrapper;
+use crate::keyring::{DataKey, KeyWrapper};
+use crate::shamir::Share;
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroizing;
+
+pub const BACKEND_NAME: &str = "shamir";
+
+/// Holds the master key reconstructed from unseal shares, in memory only.
+///
+/// The vault starts sealed. Shares submitted while sealed are collected until enough are
+/// present to reconstruct the master key; sealing drops the key again.
+pub struct Seal {
+    inner: Mutex<SealInner>,
+}
+
+#[derive(Default)]
+struct SealInner {
+    wrapper: Option<Arc<LocalKeyWrapper>>,
+    pending_shares: Vec<Share>,
+}
+
+impl Seal {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(SealInner::default()),
+        }
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.inner.lock().unwrap().wrapper.is_none()
+    }
+
+    /// Number of distinct shares submitted towards the next unseal.
+    pub fn progress(&self) -> usize {
+        self.inner.lock().unwrap().pending_shares.len()
+    }
+
+    /// Adds a share towards the next unseal, ignoring a share already submitted, and
+    /// returns the number of distinct pending shares.
+    pub fn add_share(&self, share: Share) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner
+            .pending_shares
+            .iter()
+            .any(|pending| pending.x == share.x)
+        {
+            inner.pending_shares.push(share);
+        }
+        inner.pending_shares.len()
+    }
+
+    pub fn take_pending_shares(&self) -> Vec<Share> {
+        std::mem::take(&mut self.inner.lock().unwrap().pending_shares)
+    }
+
+    pub fn unseal(&self, master_key: Zeroizing<Vec<u8>>) -> Result<(), AppError> {
+        let wrapper = LocalKeyWrapper::new(master_key)?;
+        let mut inner = self.inner.lock().unwrap();
+        inner.wrapper = Some(Arc::new(wrapper));
+        inner.pending_shares.clear();
+        Ok(())
+    }
+
+    /// Drops the master key and any pending shares.
+    pub fn seal(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.wrapper = None;
+        inner.pending_shares.clear();
+    }
+
+    fn wrapper(&self) -> Result<Arc<LocalKeyWrapper>, AppError> {
+        self.inner
+            .lock()
+            .unwrap()
+            .wrapper
+            .clone()
+            .ok_or(AppError::Sealed)
+    }
+}
+
+/// Wraps data keys under the Shamir-split master key, exactly as `LocalKeyWrapper` does
+/// under a configured one. Every operation fails while the vault is sealed.
+pub struct ShamirKeyWrapper {
+    seal: Arc<Seal>,
+}
+
+impl ShamirKeyWrapper {
+    pub fn new(seal: Arc<Seal>) -> Self {
+        Self { seal }
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyWrapper for ShamirKeyWrapper {
+    async fn generate_data_key(
+        &self,
+        key_ref: &str,
+        context: &EncryptionContext,
+    ) -> Result<DataKey, AppError> {
+        self.seal
+            .wrapper()?
+            .generate_data_key(key_ref, context)
+            .await
+    }
+
+    async fn wrap_data_key(
+        &self,
+        key_ref: &str,
+        plaintext: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Vec<u8>, AppError> {
+        self.seal
+            .wrapper()?
+            .wrap_data_key(key_ref, plaintext, context)
+            .await
+    }
+
+    async fn unwrap_data_key(
+        &self,
+        key_ref: &str,
+        wrapped_key: &[u8],
+        context: &EncryptionContext,
+    ) -> Result<Zeroizing<Vec<u8>>, AppError> {
+        self.seal
+            .wrapper()?
+            .unwrap_data_key(key_ref, wrapped_key, context)
+            .await
+    }
+}
@@ -2,30 +2,61 @@ mod config;
 mod crypto;
 mod errors;
 mod handlers;
+mod jwt;
+mod keyring;
 mod middleware;
 mod models;
+mod nonce_cache;
+mod rate_limit;
 mod regex;
 mod repositories;
 mod routes;
 mod services;
+mod shamir;
+mod signing;
 mod state;
+mod tls;
 mod validators;
 
 use crate::config::AppConfig;
+use crate::jwt::{JwksSource, JwtVerifier};
+use crate::keyring::Keyring;
+use crate::keyring::aws_kms::AwsKmsKeyWrapper;
+use crate::keyring::cache::DekCache;
+use crate::keyring::local::LocalKeyWrapper;
+use crate::keyring::shamir::{Seal, ShamirKeyWrapper};
+use crate::nonce_cache::NonceCache;
+use crate::rate_limit::{RateLimiter, RouteClass};
 use crate::routes::configure_routes;
+use crate::services::backfill::BackfillService;
+use crate::services::health::ReadinessCache;
+use crate::services::keks::KekService;
+use crate::services::purge::SecretPurgeService;
+use crate::services::replay::ReplayService;
+use crate::services::rotation::KekRotationService;
+use crate::services::session_tokens::SessionTokenService;
+use crate::signing::ClientVerifyingKey;
 use crate::state::AppState;
+use crate::tls::{ReloadableTlsConfig, TlsFiles};
 use axum::{Router, middleware as axum_middleware};
 use lockset_vault_provider::VaultProviderFactory;
 use lockset_vault_provider_aws::AwsSecretsManagerFactory;
-use p256::ecdsa::VerifyingKey;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use std::collections::HashMap;
 use std::error::Error;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
+use zeroize::Zeroizing;
+
+const DEK_CACHE_STATS_INTERVAL: Duration = Duration::from_secs(60);
+const RATE_LIMIT_STATS_INTERVAL: Duration = Duration::from_secs(60);
+const MIN_FINGERPRINT_KEY_SIZE: usize = 32;
+const NONCE_PRUNE_INTERVAL: Duration = Duration::from_secs(30);
+const SESSION_TOKEN_PRUNE_INTERVAL: Duration = Duration::from_secs(300);
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -35,13 +66,58 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let db_pool = create_db_pool(config).await?;
 
     let app_state = build_app_state(db_pool, config).await?;
+
+    if let Some(key_ref) = &config.kek_bootstrap_key_ref {
+        KekService::bootstrap_kek(&app_state, &config.kek_bootstrap_backend, key_ref).await?;
+    }
+
+    KekRotationService::spawn_worker(
+        app_state.clone(),
+        Duration::from_secs(config.kek_rotation_interval_secs),
+        config.kek_rotation_batch_size,
+    );
+    spawn_dek_cache_stats_reporter(app_state.clone());
+    spawn_rate_limit_stats_reporter(app_state.clone());
+    ReplayService::spawn_pruner(app_state.clone(), NONCE_PRUNE_INTERVAL);
+    SessionTokenService::spawn_pruner(app_state.clone(), SESSION_TOKEN_PRUNE_INTERVAL);
+    SecretPurgeService::spawn_purger(
+        app_state.clone(),
+        Duration::from_secs(config.secret_purge_interval_secs),
+        config.secret_purge_batch_size,
+    );
+
+    if config.encryption_context_backfill {
+        BackfillService::spawn_encryption_context_backfill(
+            app_state.clone(),
+            config.backfill_batch_size,
+        );
+    }
+
+    if config.fingerprint_backfill {
+        BackfillService::spawn_fingerprint_backfill(app_state.clone(), config.backfill_batch_size);
+    }
+
     let app = build_router(app_state);
 
     // Start the server
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
     info!("Starting server on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    match setup_tls(config)? {
+        Some(tls_config) => {
+            tls_config
+                .clone()
+                .spawn_reloader(Duration::from_secs(config.tls_reload_interval_secs));
+            tls::serve(listener, app, tls_config).await?;
+        }
+        None => {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await?
+        }
+    }
 
     Ok(())
 }
@@ -50,22 +126,46 @@ async fn build_app_state(
     db_pool: Pool<Postgres>,
     config: &'static AppConfig,
 ) -> Result<Arc<AppState>, Box<dyn Error>> {
-    // Load AWS config and create KMS client
-    let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-    let kms_client = aws_sdk_kms::Client::new(&aws_config);
-    info!("AWS KMS client created.");
+    let seal = config.shamir_seal_enabled.then(|| Arc::new(Seal::new()));
+    let keyring = setup_keyring(config, seal.clone()).await?;
 
-    // Create verifying key from public key
-    let public_key_bytes = hex::decode(&config.auth_public_key)?;
-    let verifying_key = VerifyingKey::from_sec1_bytes(&public_key_bytes)?;
+    // Create the legacy verifying key for requests without a key ID
+    let verifying_key = match &config.auth_public_key {
+        Some(public_key) => Some(Arc::new(ClientVerifyingKey::parse(public_key)?)),
+        None => {
+            warn!("AUTH_PUBLIC_KEY is not set; only registered client keys are accepted");
+            None
+        }
+    };
+
+    let jwt_verifier = setup_jwt_verifier(config).await?.map(Arc::new);
+    if let Some(verifier) = &jwt_verifier {
+        JwtVerifier::spawn_refresher(verifier.clone());
+    }
 
     let provider_factories = setup_vault_providers();
 
     // Create shared application state
     let app_state = Arc::new(AppState {
         db: db_pool,
-        kms_client: Arc::new(kms_client),
-        auth_verifying_key: Arc::new(verifying_key),
+        keyring: Arc::new(keyring),
+        seal,
+        auth_verifying_key: verifying_key,
+        jwt_verifier,
+        nonce_cache: Arc::new(NonceCache::new()),
+        replay_cache_shared: config.replay_cache_shared,
+        rate_limiter: Arc::new(RateLimiter::new(
+            config.rate_limit_reads,
+            config.rate_limit_writes,
+            config.rate_limit_connection_admin,
+            config.rate_limit_ips,
+            config.client_max_concurrent_requests,
+        )),
+        readiness_cache: Arc::new(ReadinessCache::new(Duration::from_secs(
+            config.readiness_cache_secs,
+        ))),
+        readiness_check_providers: config.readiness_check_providers,
+        secret_recovery_window: chrono::Duration::seconds(config.secret_recovery_window_secs),
         provider_factories: Arc::new(provider_factories),
     });
 
@@ -76,10 +176,25 @@ fn build_router(app_state: Arc<AppState>) -> Router {
     configure_routes(Router::new())
         .layer(axum_middleware::from_fn_with_state(
             app_state.clone(),
-            middleware::auth::verify_signature,
+            middleware::seal::require_unsealed,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            middleware::rate_limit::limit_clients,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            middleware::auth::authenticate,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            middleware::rate_limit::limit_ips,
         ))
         .layer(axum_middleware::from_fn(middleware::logging::log_requests))
-        .layer(axum_middleware::from_fn(middleware::healthcheck::healthcheck))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            middleware::healthcheck::healthcheck,
+        ))
         .with_state(app_state)
 }
 
@@ -104,6 +219,170 @@ fn setup_logging() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+async fn setup_keyring(
+    config: &AppConfig,
+    seal: Option<Arc<Seal>>,
+) -> Result<Keyring, Box<dyn Error>> {
+    if config.fingerprint_key.is_none() {
+        warn!(
+            "FINGERPRINT_KEY is not set; unkeyed SHA-256 fingerprints, which can confirm guessed secret values, are written"
+        );
+    }
+    let mut keyring = Keyring::new(
+        DekCache::new(
+            config.dek_cache_capacity,
+            Duration::from_secs(config.dek_cache_ttl_secs),
+        ),
+        config.data_encryption_algorithm,
+        config
+            .fingerprint_key
+            .as_deref()
+            .map(|key| decode_fingerprint_key(key))
+            .transpose()?,
+        config.require_encryption_context,
+    );
+
+    if config.aws_kms_enabled {
+        // Load AWS config and create KMS client
+        let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let kms_client = aws_sdk_kms::Client::new(&aws_config);
+        keyring.register(
+            keyring::aws_kms::BACKEND_NAME,
+            Box::new(AwsKmsKeyWrapper::new(kms_client)),
+        );
+        info!(
+            "AWS KMS key wrapper registered: {}",
+            keyring::aws_kms::BACKEND_NAME
+        );
+    }
+
+    if let Some(master_key) = &config.local_master_key {
+        keyring.register(
+            keyring::local::BACKEND_NAME,
+            Box::new(LocalKeyWrapper::from_hex(master_key)?),
+        );
+        info!(
+            "Local key wrapper registered: {}",
+            keyring::local::BACKEND_NAME
+        );
+    }
+
+    if let Some(seal) = seal {
+        keyring.register(
+            keyring::shamir::BACKEND_NAME,
+            Box::new(ShamirKeyWrapper::new(seal)),
+        );
+        info!(
+            "Shamir key wrapper registered, vault is sealed: {}",
+            keyring::shamir::BACKEND_NAME
+        );
+    }
+
+    Ok(keyring)
+}
+
+fn setup_tls(config: &AppConfig) -> Result<Option<Arc<ReloadableTlsConfig>>, Box<dyn Error>> {
+    let (Some(cert_file), Some(key_file)) = (&config.tls_cert_file, &config.tls_key_file) else {
+        return Ok(None);
+    };
+
+    let tls_config = ReloadableTlsConfig::load(TlsFiles {
+        cert_file: cert_file.into(),
+        key_file: key_file.into(),
+        client_ca_file: config.tls_client_ca_file.as_ref().map(Into::into),
+        client_auth_required: config.tls_client_auth_required,
+    })?;
+    info!(
+        client_certificates = config.tls_client_ca_file.is_some(),
+        "TLS enabled"
+    );
+    Ok(Some(Arc::new(tls_config)))
+}
+
+async fn setup_jwt_verifier(config: &AppConfig) -> Result<Option<JwtVerifier>, Box<dyn Error>> {
+    let (Some(issuer), Some(audience)) = (&config.jwt_issuer, &config.jwt_audience) else {
+        return Ok(None);
+    };
+    let source = match (&config.jwt_jwks_url, &config.jwt_jwks_file) {
+        (Some(url), _) => JwksSource::Url(url.clone()),
+        (None, Some(path)) => JwksSource::File(path.into()),
+        (None, None) => return Err("JWT_JWKS_URL or JWT_JWKS_FILE must be set".into()),
+    };
+
+    let verifier = JwtVerifier::new(
+        issuer.clone(),
+        audience.clone(),
+        config.jwt_allowed_namespaces.clone(),
+        source,
+        Duration::from_secs(config.jwt_jwks_refresh_secs),
+    )
+    .await?;
+    info!("Bearer token authentication enabled for issuer {}", issuer);
+    Ok(Some(verifier))
+}
+
+fn decode_fingerprint_key(fingerprint_key_hex: &str) -> Result<Zeroizing<Vec<u8>>, Box<dyn Error>> {
+    let fingerprint_key = Zeroizing::new(hex::decode(fingerprint_key_hex.trim())?);
+    if fingerprint_key.len() < MIN_FINGERPRINT_KEY_SIZE {
+        return Err(format!(
+            "FINGERPRINT_KEY must be at least {} bytes, got {}",
+            MIN_FINGERPRINT_KEY_SIZE,
+            fingerprint_key.len()
+        )
+        .into());
+    }
+    Ok(fingerprint_key)
+}
+
+fn spawn_dek_cache_stats_reporter(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(DEK_CACHE_STATS_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let stats = state.keyring.dek_cache().stats();
+            info!(
+                dek_cache_hits = stats.hits,
+                dek_cache_misses = stats.misses,
+                dek_cache_size = stats.size,
+                "DEK cache stats"
+            );
+        }
+    });
+}
+
+fn spawn_rate_limit_stats_reporter(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(RATE_LIMIT_STATS_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let rate_limiter = &state.rate_limiter;
+            rate_limiter.prune();
+            for class in [
+                RouteClass::Read,
+                RouteClass::Write,
+                RouteClass::ConnectionAdmin,
+            ] {
+                let stats = rate_limiter.stats(class);
+                info!(
+                    route_class = ?class,
+                    rate_limit_allowed = stats.allowed,
+                    rate_limit_throttled = stats.throttled,
+                    rate_limit_clients = stats.tracked,
+                    "Rate limit stats"
+                );
+            }
+            let ip_stats = rate_limiter.ip_stats();
+            info!(
+                rate_limit_allowed = ip_stats.allowed,
+                rate_limit_throttled = ip_stats.throttled,
+                rate_limit_ips = ip_stats.tracked,
+                concurrency_rejected = rate_limiter.concurrency_rejected(),
+                "IP rate limit stats"
+            );
+        }
+    });
+}
+
 fn setup_vault_providers() -> HashMap<String, Box<dyn VaultProviderFactory + Send + Sync>> {
     // Create provider factories
     let mut provider_factories: HashMap<String, Box<dyn VaultProviderFactory + Send + Sync>> =
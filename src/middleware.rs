@@ -1,3 +1,5 @@
 pub mod auth;
 pub mod healthcheck;
 pub mod logging;
+pub mod rate_limit;
+pub mod seal;
@@ -1,18 +1,133 @@
-use crate::{errors::AppError, state::AppState};
+use crate::{
+    errors::AppError,
+    models::ClientIdentity,
+    regex::get_nonce_regex,
+    services::{
+        client_keys::ClientKeyService,
+        replay::ReplayService,
+        session_tokens::{SESSION_TOKEN_PREFIX, SessionTokenService},
+    },
+    state::AppState,
+    tls::ClientCertificate,
+};
 use axum::{
     body::{Body, to_bytes},
     extract::{Request, State},
-    http::Method,
+    http::{Method, header::AUTHORIZATION, request::Parts},
     middleware::Next,
     response::Response,
 };
 use bytes::Bytes;
-use ecdsa::{Signature, signature::Verifier};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
 use std::sync::Arc;
+use tracing::{info, warn};
 
 const MAX_BODY_SIZE: usize = 256 * 1024; // 256kb
 const MAX_TIMESTAMP_DIFF_MS: i64 = 5000; // 5 seconds
 
+/// Headers covered by a version 2 signature, in canonical order.
+const SIGNED_HEADERS: [&str; 2] = ["content-type", "idempotency-key"];
+
+/// The format of the plaintext a request signature covers, chosen by the client with the
+/// `X-Signature-Version` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignatureVersion {
+    /// `timestamp\nnonce\npath\nbody`. Deprecated: it does not cover the method or
+    /// query string.
+    V1,
+    /// The canonical request built by `canonical_request`.
+    V2,
+}
+
+impl FromStr for SignatureVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(SignatureVersion::V1),
+            "2" => Ok(SignatureVersion::V2),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Authenticates a request by its session token if it bears one, else by its
+/// `Authorization: Bearer` JWT if bearer tokens are configured and one is present, else
+/// by its TLS client certificate if it has one and is not signed, and by its signature
+/// otherwise.
+pub async fn authenticate(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if let Some(token) = bearer_token(&req) {
+        if token.starts_with(SESSION_TOKEN_PREFIX) {
+            return verify_session_token(State(state), req, next).await;
+        }
+        if state.jwt_verifier.is_some() {
+            return verify_bearer_token(State(state), req, next).await;
+        }
+    }
+    if !req.headers().contains_key("X-Signature")
+        && req.extensions().get::<ClientCertificate>().is_some()
+    {
+        return verify_client_certificate(req, next).await;
+    }
+    verify_signature(State(state), req, next).await
+}
+
+/// Authenticates a request by the client certificate its TLS connection was verified
+/// with.
+pub async fn verify_client_certificate(mut req: Request, next: Next) -> Result<Response, AppError> {
+    let certificate = req
+        .extensions()
+        .get::<ClientCertificate>()
+        .ok_or(AppError::Unauthorized)?;
+    let identity = ClientIdentity::client_certificate(&certificate.name);
+
+    info!(client = %identity.name, "request authenticated with client certificate");
+
+    req.extensions_mut().insert(identity);
+    Ok(next.run(req).await)
+}
+
+/// Authenticates a request by a session token issued by `POST /v1/auth/login`.
+pub async fn verify_session_token(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let token = bearer_token(&req).ok_or(AppError::Unauthorized)?;
+    let identity = SessionTokenService::resolve_session_token(&state.db, token).await?;
+
+    info!(client = %identity.name, key_id = ?identity.key_id, "request authenticated with session token");
+
+    req.extensions_mut().insert(identity);
+    Ok(next.run(req).await)
+}
+
+pub async fn verify_bearer_token(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let jwt_verifier = state.jwt_verifier.as_ref().ok_or(AppError::Unauthorized)?;
+
+    let token = bearer_token(&req).ok_or(AppError::Unauthorized)?;
+    let identity = jwt_verifier.verify(token).await?;
+
+    info!(
+        client = %identity.name,
+        namespace = identity.namespace.as_deref().unwrap_or_default(),
+        audience = identity.audience.as_deref().unwrap_or_default(),
+        "request authenticated with bearer token"
+    );
+
+    req.extensions_mut().insert(identity);
+    Ok(next.run(req).await)
+}
+
 pub async fn verify_signature(
     State(state): State<Arc<AppState>>,
     req: Request,
@@ -36,6 +151,18 @@ pub async fn verify_signature(
         return Err(AppError::Unauthorized);
     };
 
+    let nonce = if let Some(nonce) = parts.headers.get("X-Nonce") {
+        nonce
+            .to_str()
+            .map_err(|_| AppError::InvalidInput("Invalid X-Nonce header".to_string()))?
+    } else {
+        return Err(AppError::Unauthorized);
+    };
+
+    if !get_nonce_regex().is_match(nonce) {
+        return Err(AppError::InvalidInput("Invalid X-Nonce format".to_string()));
+    }
+
     let timestamp_ms = timestamp_str
         .parse::<i64>()
         .map_err(|_| AppError::InvalidInput("Invalid X-Timestamp format".to_string()))?;
@@ -48,10 +175,8 @@ pub async fn verify_signature(
         ));
     }
 
-    let signature_bytes = hex::decode(signature_hex)
+    let signature = hex::decode(signature_hex)
         .map_err(|_| AppError::InvalidInput("Invalid signature format".to_string()))?;
-    let signature = Signature::from_slice(&signature_bytes)
-        .map_err(|_| AppError::InvalidInput("Invalid signature".to_string()))?;
 
     let body_bytes = if parts.method == Method::GET || parts.method == Method::DELETE {
         Bytes::new()
@@ -61,20 +186,247 @@ pub async fn verify_signature(
             .map_err(|_| AppError::InvalidInput("Request body too large".to_string()))?
     };
 
+    let signature_version = match parts.headers.get("X-Signature-Version") {
+        Some(version) => version
+            .to_str()
+            .ok()
+            .and_then(|version| version.parse().ok())
+            .ok_or_else(|| AppError::InvalidInput("Unsupported X-Signature-Version".to_string()))?,
+        None => SignatureVersion::V1,
+    };
+
+    let plaintext = match signature_version {
+        SignatureVersion::V1 => {
+            legacy_signing_payload(timestamp_str, nonce, parts.uri.path(), &body_bytes)
+        }
+        SignatureVersion::V2 => canonical_request(&parts, timestamp_str, nonce, &body_bytes)?,
+    };
+
+    let (verifying_key, identity) = match parts.headers.get("X-Key-Id") {
+        Some(key_id) => {
+            let key_id = key_id
+                .to_str()
+                .map_err(|_| AppError::InvalidInput("Invalid X-Key-Id header".to_string()))?;
+            ClientKeyService::resolve_client_key(&state.db, key_id).await?
+        }
+        None => {
+            let verifying_key = state
+                .auth_verifying_key
+                .as_deref()
+                .ok_or(AppError::Unauthorized)?;
+            (verifying_key.clone(), ClientIdentity::legacy())
+        }
+    };
+
+    verifying_key.verify(&plaintext, &signature)?;
+
+    // Only verified requests are recorded, so forged ones cannot burn a client's nonces.
+    // A nonce is remembered until its timestamp falls outside the recv window.
+    let nonce_expires_at =
+        chrono::DateTime::from_timestamp_millis(timestamp_ms + MAX_TIMESTAMP_DIFF_MS)
+            .ok_or_else(|| AppError::InvalidInput("Invalid X-Timestamp format".to_string()))?;
+    ReplayService::check_nonce(&state, &identity.name, nonce, nonce_expires_at).await?;
+
+    if signature_version == SignatureVersion::V1 {
+        warn!(client = %identity.name, "request signed with deprecated signature version 1");
+    }
+
+    info!(client = %identity.name, key_id = ?identity.key_id, "request authenticated");
+
+    let mut req = Request::from_parts(parts, Body::from(body_bytes));
+    req.extensions_mut().insert(identity);
+
+    Ok(next.run(req).await)
+}
+
+fn legacy_signing_payload(timestamp: &str, nonce: &str, path: &str, body: &[u8]) -> Vec<u8> {
     let mut plaintext =
-        Vec::with_capacity(timestamp_str.len() + parts.uri.path().len() + body_bytes.len() + 2);
-    plaintext.extend_from_slice(timestamp_str.as_bytes());
+        Vec::with_capacity(timestamp.len() + nonce.len() + path.len() + body.len() + 3);
+    plaintext.extend_from_slice(timestamp.as_bytes());
     plaintext.push(b'\n');
-    plaintext.extend_from_slice(parts.uri.path().as_bytes());
+    plaintext.extend_from_slice(nonce.as_bytes());
     plaintext.push(b'\n');
-    plaintext.extend_from_slice(body_bytes.as_ref());
+    plaintext.extend_from_slice(path.as_bytes());
+    plaintext.push(b'\n');
+    plaintext.extend_from_slice(body);
+    plaintext
+}
 
-    state
-        .auth_verifying_key
-        .verify(&plaintext, &signature)
-        .map_err(|_| AppError::Unauthorized)?;
+fn bearer_token(req: &Request) -> Option<&str> {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
 
-    let req = Request::from_parts(parts, Body::from(body_bytes));
+/// Builds the version 2 canonical request, one element per line:
+///
+/// ```text
+/// LOCKSET-V2
+/// METHOD
+/// /path
+/// sorted&query=string
+/// timestamp
+/// nonce
+/// content-type:value
+/// idempotency-key:value
+/// hex(sha256(body))
+/// ```
+///
+/// Query parameters are sorted as sent, without decoding. Missing headers are signed
+/// with an empty value.
+fn canonical_request(
+    parts: &Parts,
+    timestamp: &str,
+    nonce: &str,
+    body: &[u8],
+) -> Result<Vec<u8>, AppError> {
+    let mut query_params: Vec<&str> = parts
+        .uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .collect();
+    query_params.sort_unstable();
 
-    Ok(next.run(req).await)
+    let mut lines = vec![
+        "LOCKSET-V2".to_string(),
+        parts.method.as_str().to_string(),
+        parts.uri.path().to_string(),
+        query_params.join("&"),
+        timestamp.to_string(),
+        nonce.to_string(),
+    ];
+    for name in SIGNED_HEADERS {
+        let value = match parts.headers.get(name) {
+            Some(value) => value
+                .to_str()
+                .map_err(|_| AppError::InvalidInput(format!("Invalid {} header", name)))?,
+            None => "",
+        };
+        lines.push(format!("{}:{}", name, value.trim()));
+    }
+    lines.push(hex::encode(Sha256::digest(body)));
+
+    Ok(lines.join("\n").into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::ClientVerifyingKey;
+    use ecdsa::signature::Signer;
+
+    const TIMESTAMP: &str = "1760000000000";
+    const NONCE: &str = "6f1c2a9e4b7d40f1a2c3d4e5f6a7b8c9";
+    const EMPTY_BODY_HASH: &str =
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn parts(method: Method, uri: &str, headers: &[(&str, &str)]) -> Parts {
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap().into_parts().0
+    }
+
+    fn canonical(parts: &Parts, body: &[u8]) -> String {
+        String::from_utf8(canonical_request(parts, TIMESTAMP, NONCE, body).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn signs_every_component_on_its_own_line() {
+        let parts = parts(
+            Method::POST,
+            "/v1/secrets/payments/api-key",
+            &[
+                ("content-type", " application/json "),
+                ("idempotency-key", "retry-1"),
+                ("x-ignored", "not signed"),
+            ],
+        );
+        assert_eq!(
+            canonical(&parts, br#"{"value":"hunter2"}"#),
+            [
+                "LOCKSET-V2",
+                "POST",
+                "/v1/secrets/payments/api-key",
+                "",
+                TIMESTAMP,
+                NONCE,
+                "content-type:application/json",
+                "idempotency-key:retry-1",
+                "416d5a002a719068d48c0296de176da20af49a749fee769f27af7c96a104fe7f",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn sorts_query_parameters_without_decoding() {
+        let parts = parts(
+            Method::GET,
+            "/v1/secrets?prefix=pay%2Fments&limit=10&&cursor=abc&limit=1",
+            &[],
+        );
+        assert_eq!(
+            canonical(&parts, b""),
+            [
+                "LOCKSET-V2",
+                "GET",
+                "/v1/secrets",
+                "cursor=abc&limit=1&limit=10&prefix=pay%2Fments",
+                TIMESTAMP,
+                NONCE,
+                "content-type:",
+                "idempotency-key:",
+                EMPTY_BODY_HASH,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn signs_missing_headers_as_empty_values() {
+        let without = parts(Method::GET, "/v1/secrets/a", &[]);
+        let empty = parts(
+            Method::GET,
+            "/v1/secrets/a",
+            &[("content-type", ""), ("idempotency-key", "")],
+        );
+        assert_eq!(canonical(&without, b""), canonical(&empty, b""));
+        assert!(canonical(&without, b"").contains("\ncontent-type:\nidempotency-key:\n"));
+    }
+
+    #[test]
+    fn binds_the_body() {
+        let parts = parts(Method::POST, "/v1/secrets/a", &[]);
+        assert!(canonical(&parts, b"").ends_with(EMPTY_BODY_HASH));
+        assert_ne!(
+            canonical(&parts, br#"{"value":"a"}"#),
+            canonical(&parts, br#"{"value":"b"}"#)
+        );
+    }
+
+    #[test]
+    fn a_signed_get_does_not_authorize_a_delete() {
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&[5; 32]).unwrap();
+        let verifying_key = ClientVerifyingKey::EcdsaP256(*signing_key.verifying_key());
+
+        let get = parts(Method::GET, "/v1/secrets/payments/api-key", &[]);
+        let get_request = canonical_request(&get, TIMESTAMP, NONCE, b"").unwrap();
+        let signature: p256::ecdsa::Signature = signing_key.sign(&get_request);
+        verifying_key
+            .verify(&get_request, &signature.to_bytes())
+            .unwrap();
+
+        let delete = parts(Method::DELETE, "/v1/secrets/payments/api-key", &[]);
+        let delete_request = canonical_request(&delete, TIMESTAMP, NONCE, b"").unwrap();
+        assert!(matches!(
+            verifying_key.verify(&delete_request, &signature.to_bytes()),
+            Err(AppError::Unauthorized)
+        ));
+    }
 }
@@ -1,15 +1,47 @@
-use axum::{extract::Request, middleware::Next, response::Response};
+use crate::{services::health::HealthService, state::AppState};
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
 
-pub async fn healthcheck(req: Request, next: Next) -> Response {
+pub async fn healthcheck(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
     let method = req.method();
     let path = req.uri().path().to_string();
 
     if method == axum::http::Method::GET && path == "/healthcheck" {
+        let sealed = state.seal.as_ref().is_some_and(|seal| seal.is_sealed());
+        let (status, body) = if sealed {
+            (axum::http::StatusCode::SERVICE_UNAVAILABLE, "SEALED")
+        } else {
+            (axum::http::StatusCode::OK, "OK")
+        };
+
         return Response::builder()
-            .status(axum::http::StatusCode::OK)
-            .body(axum::body::Body::from("OK"))
+            .status(status)
+            .body(axum::body::Body::from(body))
             .unwrap();
     }
 
+    // Liveness only reports that the process is serving requests; dependency failures
+    // should take the instance out of rotation via readiness, not restart it.
+    if method == axum::http::Method::GET && path == "/livez" {
+        return (StatusCode::OK, Json(json!({ "status": "ok" }))).into_response();
+    }
+
+    if method == axum::http::Method::GET && path == "/readyz" {
+        let (ready, response) = HealthService::readiness(&state).await;
+        let status = if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        return (status, Json(response)).into_response();
+    }
+
     next.run(req).await
 }
@@ -0,0 +1,56 @@
+use crate::{errors::AppError, models::ClientIdentity, rate_limit::RouteClass, state::AppState};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// How long clients at their concurrency quota are asked to wait before retrying.
+const CONCURRENCY_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Throttles requests by source IP address, before they are authenticated.
+pub async fn limit_ips(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
+        let ip = addr.ip().to_string();
+        if let Err(retry_after) = state.rate_limiter.acquire_ip(&ip) {
+            debug!(ip, "request rate limited by IP");
+            return Err(AppError::RateLimited(retry_after));
+        }
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Throttles authenticated requests by client and route class, and caps each client's
+/// concurrent requests.
+pub async fn limit_clients(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(identity) = req.extensions().get::<ClientIdentity>() else {
+        return Ok(next.run(req).await);
+    };
+    let client = identity.name.clone();
+    let class = RouteClass::of(req.method(), req.uri().path());
+
+    if let Err(retry_after) = state.rate_limiter.acquire_client(&client, class) {
+        debug!(client, ?class, "request rate limited");
+        return Err(AppError::RateLimited(retry_after));
+    }
+
+    let Some(_in_flight) = state.rate_limiter.start_request(&client) else {
+        debug!(client, "request rejected by concurrency quota");
+        return Err(AppError::RateLimited(CONCURRENCY_RETRY_AFTER));
+    };
+
+    Ok(next.run(req).await)
+}
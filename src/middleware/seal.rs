@@ -0,0 +1,26 @@
+use crate::{errors::AppError, state::AppState};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// Rejects `/v1` traffic other than the `/v1/sys` endpoints while the vault is sealed.
+pub async fn require_unsealed(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let path = req.uri().path();
+
+    if let Some(seal) = &state.seal
+        && seal.is_sealed()
+        && path.starts_with("/v1/")
+        && !path.starts_with("/v1/sys/")
+    {
+        return Err(AppError::Sealed);
+    }
+
+    Ok(next.run(req).await)
+}
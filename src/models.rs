@@ -1,13 +1,17 @@
+use crate::crypto::EncryptionContext;
 use crate::errors::AppError;
 use crate::regex::{get_public_id_regex, get_secret_name_regex, get_version_tag_regex};
-use crate::validators::validate_vault_config;
+use crate::validators::{validate_secret_path, validate_vault_config};
 use axum::Json;
 use axum::extract::rejection::JsonRejection;
-use axum::extract::{FromRequest, Request};
+use axum::extract::{FromRequest, FromRequestParts, Query, Request};
+use axum::http::request::Parts;
 use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use sqlx::types::Json as SqlJson;
+use std::collections::BTreeMap;
 use validator::Validate;
 use zeroize::Zeroizing;
 
@@ -36,6 +40,131 @@ where
     }
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryPayload<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for QueryPayload<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(value)) => {
+                value.validate()?;
+                Ok(Self(value))
+            }
+            Err(rejection) => Err(AppError::InvalidInput(rejection.body_text())),
+        }
+    }
+}
+
+/// The caller a request was authenticated as, added to the request extensions by the
+/// auth middleware.
+#[derive(Clone, Debug)]
+pub struct ClientIdentity {
+    /// `None` for requests not signed with a registered client key, or a session token
+    /// issued to one.
+    pub key_id: Option<String>,
+    pub name: String,
+    /// The legacy `AUTH_PUBLIC_KEY` client, which bypasses policies.
+    pub is_root: bool,
+    pub auth_method: AuthMethod,
+    /// Capabilities a session token is restricted to, on top of the client's policies.
+    pub scope: Option<Vec<Capability>>,
+    /// The Kubernetes namespace a bearer token was issued in, if it names one.
+    pub namespace: Option<String>,
+    /// The audience a bearer token was verified for.
+    pub audience: Option<String>,
+}
+
+impl ClientIdentity {
+    /// The identity of requests signed with the legacy `AUTH_PUBLIC_KEY`.
+    pub fn legacy() -> Self {
+        Self {
+            key_id: None,
+            name: "root".to_string(),
+            is_root: true,
+            auth_method: AuthMethod::Signature,
+            scope: None,
+            namespace: None,
+            audience: None,
+        }
+    }
+
+    /// The identity of requests with a bearer token, named by its `sub` claim.
+    pub fn bearer_token(subject: &str, namespace: Option<&str>, audience: &str) -> Self {
+        Self {
+            key_id: None,
+            name: format!("{}{}", BEARER_TOKEN_NAME_PREFIX, subject),
+            is_root: false,
+            auth_method: AuthMethod::BearerToken,
+            scope: None,
+            namespace: namespace.map(str::to_string),
+            audience: Some(audience.to_string()),
+        }
+    }
+
+    /// The identity of requests with a client certificate, named by its SAN or CN.
+    pub fn client_certificate(name: &str) -> Self {
+        Self {
+            key_id: None,
+            name: format!("{}{}", CLIENT_CERTIFICATE_NAME_PREFIX, name),
+            is_root: false,
+            auth_method: AuthMethod::ClientCertificate,
+            scope: None,
+            namespace: None,
+            audience: None,
+        }
+    }
+
+    /// Whether `name` is in a namespace reserved for bearer token or client certificate
+    /// identities, and so cannot name a client key.
+    pub fn is_reserved_name(name: &str) -> bool {
+        name.starts_with(BEARER_TOKEN_NAME_PREFIX)
+            || name.starts_with(CLIENT_CERTIFICATE_NAME_PREFIX)
+    }
+}
+
+/// Prefixes of the names of identities not backed by a client key, so that a token
+/// subject or certificate name cannot take on the policies of a client key's name.
+pub const BEARER_TOKEN_NAME_PREFIX: &str = "jwt:";
+pub const CLIENT_CERTIFICATE_NAME_PREFIX: &str = "cert:";
+
+/// How a request was authenticated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthMethod {
+    Signature,
+    BearerToken,
+    ClientCertificate,
+    SessionToken,
+}
+
+/// An operation a policy can grant on the resources matching its pattern. `Admin`
+/// implies every other capability.
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "text", rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    Read,
+    Create,
+    CreateVersion,
+    ManageConnections,
+    Admin,
+}
+
+/// The algorithm a client key signs requests with, determined by the key itself.
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "text", rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureAlgorithm {
+    EcdsaP256,
+    EcdsaP384,
+    Ed25519,
+}
+
 pub struct VaultConnectionConfig {
     pub id: i32,
     pub integration_type: String,
@@ -53,6 +182,10 @@ pub struct CreateSecretRequest {
         path = "get_secret_name_regex()",
         message = "Invalid secret name format"
     ))]
+    #[validate(custom(
+        function = "validate_secret_path",
+        message = "Secret name segments must be non-empty and cannot be `.`, `..` or `versions`"
+    ))]
     #[validate(length(
         min = 1,
         max = 255,
@@ -92,6 +225,8 @@ pub struct CreateSecretVersionRequest {
         message = "Version tag must be between 1 and 20 characters"
     ))]
     pub version_tag: String,
+    /// Whether the new version becomes current. Defaults to `true`.
+    pub promote: Option<bool>,
 }
 
 #[derive(Serialize, Debug)]
@@ -113,6 +248,160 @@ pub struct CreateSecretVersionResponse {
     pub name: String,
     pub version_tag: String,
     pub created_at: DateTime<Utc>,
+    pub promoted: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PromoteSecretVersionResponse {
+    pub name: String,
+    pub current_version: String,
+    pub previous_version: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DestroySecretResponse {
+    pub name: String,
+    pub destroyed_versions: Vec<String>,
+    pub destroyed_at: DateTime<Utc>,
+}
+
+/// The immediate children of a folder: the next segment of every secret name under it.
+#[derive(Serialize, Debug)]
+pub struct FolderResponse {
+    pub prefix: String,
+    pub folders: Vec<String>,
+    pub secrets: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct ListSecretsQuery {
+    /// Only secrets whose name starts with this string.
+    #[validate(length(max = 255, message = "Prefix must be at most 255 characters"))]
+    pub prefix: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    /// Only secrets backed by a vault connection (`true`) or holding static values (`false`).
+    pub proxied: Option<bool>,
+    /// List soft-deleted secrets instead of live ones.
+    #[serde(default)]
+    pub deleted: bool,
+    /// The order of `created_at`.
+    #[serde(default)]
+    pub order: SortOrder,
+    #[validate(range(min = 1, max = 1000, message = "Limit must be between 1 and 1000"))]
+    pub limit: Option<i64>,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SecretMetadataResponse {
+    pub name: String,
+    pub current_version: Option<String>,
+    pub previous_version: Option<String>,
+    /// The public ID of the vault connection a proxied secret is read through.
+    pub vault_connection: Option<String>,
+    pub expire_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl From<SecretListing> for SecretMetadataResponse {
+    fn from(listing: SecretListing) -> Self {
+        Self {
+            name: listing.name,
+            current_version: listing.current_version,
+            previous_version: listing.previous_version,
+            vault_connection: listing.vault_connection,
+            expire_at: listing.expire_at,
+            created_at: listing.created_at,
+            updated_at: listing.updated_at,
+            deleted_at: listing.deleted_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ListSecretsResponse {
+    pub secrets: Vec<SecretMetadataResponse>,
+    /// Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct ListSecretVersionsQuery {
+    #[validate(range(min = 1, max = 1000, message = "Limit must be between 1 and 1000"))]
+    pub limit: Option<i64>,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SecretVersionMetadataResponse {
+    pub version_tag: String,
+    /// `None` once the version has been destroyed.
+    pub fingerprint: Option<String>,
+    pub current: bool,
+    pub previous: bool,
+    pub deleted: bool,
+    pub expire_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub destroyed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ListSecretVersionsResponse {
+    pub name: String,
+    pub current_version: Option<String>,
+    pub previous_version: Option<String>,
+    /// Newest first.
+    pub versions: Vec<SecretVersionMetadataResponse>,
+    /// Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct DeleteSecretQuery {
+    /// Allow deleting the current version, in which case the previous version, if any,
+    /// becomes current.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DeleteSecretResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_tag: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    /// When the purger hard-deletes it unless it is restored first.
+    pub purge_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RestoreSecretResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_tag: Option<String>,
+    pub current_version: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DestroyFolderResponse {
+    pub prefix: String,
+    pub secrets: Vec<DestroySecretResponse>,
+    pub destroyed_at: DateTime<Utc>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Validate)]
@@ -171,25 +460,274 @@ pub struct VaultConnectionResponse {
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct CreateKekRequest {
+    #[validate(length(min = 1, message = "Backend cannot be empty"))]
+    pub backend: String,
+    #[validate(length(min = 1, message = "KMS key cannot be empty"))]
+    pub kms_key: String,
+    #[validate(range(min = 0, message = "Weight cannot be negative"))]
+    pub weight: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct UpdateKekRequest {
+    pub state: Option<KekState>,
+    #[validate(range(min = 0, message = "Weight cannot be negative"))]
+    pub weight: Option<i32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct KekResponse {
+    pub id: i32,
+    pub backend: String,
+    pub kms_key: String,
+    pub state: KekState,
+    pub weight: i32,
+    pub dek_count: i64,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<KekUsage> for KekResponse {
+    fn from(usage: KekUsage) -> Self {
+        Self {
+            id: usage.kek.id,
+            backend: usage.kek.backend,
+            kms_key: usage.kek.kms_key,
+            state: usage.kek.state,
+            weight: usage.kek.weight,
+            dek_count: usage.dek_count,
+            verified_at: usage.kek.verified_at,
+            created_at: usage.kek.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct CreateClientKeyRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    #[validate(length(min = 1, message = "Public key cannot be empty"))]
+    pub public_key: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ClientKeyResponse {
+    pub key_id: String,
+    pub name: String,
+    pub algorithm: SignatureAlgorithm,
+    pub public_key: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked: bool,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ClientKey> for ClientKeyResponse {
+    fn from(key: ClientKey) -> Self {
+        Self {
+            key_id: key.key_id,
+            name: key.name,
+            algorithm: key.algorithm,
+            public_key: key.public_key,
+            expires_at: key.expires_at,
+            revoked: key.revoked,
+            revoked_at: key.revoked_at,
+            created_at: key.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct CreatePolicyRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Client name must be between 1 and 255 characters"
+    ))]
+    pub client_name: String,
+    #[validate(length(min = 1, message = "At least one capability is required"))]
+    pub capabilities: Vec<Capability>,
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Pattern must be between 1 and 255 characters"
+    ))]
+    pub pattern: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub checks: BTreeMap<String, CheckResult>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Error,
+    Skipped,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CheckResult {
+    pub status: CheckStatus,
+    /// Why the check failed or was skipped, for the logs only.
+    #[serde(skip)]
+    pub detail: Option<String>,
+}
+
+impl CheckResult {
+    pub fn ok() -> Self {
+        Self {
+            status: CheckStatus::Ok,
+            detail: None,
+        }
+    }
+
+    pub fn error(detail: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Error,
+            detail: Some(detail.into()),
+        }
+    }
+
+    pub fn skipped(detail: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Skipped,
+            detail: Some(detail.into()),
+        }
+    }
+
+    /// Whether the check does not prevent the instance from serving requests.
+    pub fn is_healthy(&self) -> bool {
+        self.status != CheckStatus::Error
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct LoginRequest {
+    /// Lifetime of the token in seconds, up to an hour. Defaults to 15 minutes.
+    #[validate(range(
+        min = 1,
+        max = 3600,
+        message = "TTL must be between 1 and 3600 seconds"
+    ))]
+    pub ttl_secs: Option<i64>,
+    /// Restricts the token to these capabilities. Defaults to all the client is granted.
+    #[validate(length(min = 1, message = "At least one capability is required"))]
+    pub capabilities: Option<Vec<Capability>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LoginResponse {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub capabilities: Option<Vec<Capability>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PolicyResponse {
+    pub id: i32,
+    pub client_name: String,
+    pub capabilities: Vec<Capability>,
+    pub pattern: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Policy> for PolicyResponse {
+    fn from(policy: Policy) -> Self {
+        Self {
+            id: policy.id,
+            client_name: policy.client_name,
+            capabilities: policy.capabilities,
+            pattern: policy.pattern,
+            created_at: policy.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct InitSealRequest {
+    #[validate(range(min = 1, max = 255, message = "Shares must be between 1 and 255"))]
+    pub shares: i32,
+    #[validate(range(min = 1, max = 255, message = "Threshold must be between 1 and 255"))]
+    pub threshold: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct InitSealResponse {
+    pub shares: Vec<Zeroizing<String>>,
+    pub threshold: i32,
+}
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct UnsealRequest {
+    #[validate(length(min = 1, message = "Share cannot be empty"))]
+    pub share: Zeroizing<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SealStatusResponse {
+    pub initialized: bool,
+    pub sealed: bool,
+    pub threshold: Option<i32>,
+    pub shares: Option<i32>,
+    pub progress: usize,
+}
+
 // =================================================================
 // Database Model Structs
 // =================================================================
 
+/// Lifecycle state of a key encryption key.
+///
+/// Only `Active` KEKs wrap new DEKs. DEKs under a `DecryptOnly` KEK are re-wrapped
+/// under an active KEK in the background, after which the KEK becomes `Retired`.
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum KekState {
+    Active,
+    DecryptOnly,
+    Retired,
+}
+
 #[derive(FromRow, Debug, Clone)]
 pub struct KeyEncryptionKey {
     pub id: i32,
     pub kms_key: String,
+    pub backend: String,
+    pub state: KekState,
+    pub weight: i32,
+    pub verified_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(FromRow, Debug)]
+pub struct KekUsage {
+    #[sqlx(flatten)]
+    pub kek: KeyEncryptionKey,
+    pub dek_count: i64,
+}
+
 #[derive(FromRow, Debug)]
 pub struct DataEncryptionKey {
     pub id: i32,
     pub key_id: String,
     pub kek_id: i32,
-    pub encrypted_key: String,
+    pub encrypted_key: Vec<u8>,
     pub algo: String,
     pub created_at: DateTime<Utc>,
+    /// `None` for DEKs created before encryption contexts were introduced.
+    pub encryption_context: Option<SqlJson<EncryptionContext>>,
 }
 
 #[derive(FromRow, Debug)]
@@ -198,7 +736,7 @@ pub struct VaultConnection {
     pub public_id: String,
     pub integration_type: String,
     pub sha256sum: String,
-    pub encrypted_config: String,
+    pub encrypted_config: Vec<u8>,
     pub dek_id: i32,
     pub ttl: Option<i32>,
     pub created_at: DateTime<Utc>,
@@ -215,6 +753,33 @@ pub struct Secret {
     pub expire_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// A secret's metadata with the public ID of its vault connection, as listed.
+#[derive(FromRow, Debug)]
+pub struct SecretListing {
+    pub id: i32,
+    pub name: String,
+    pub current_version: Option<String>,
+    pub previous_version: Option<String>,
+    pub vault_connection: Option<String>,
+    pub expire_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// A version's metadata, without its ciphertext, as listed.
+#[derive(FromRow, Debug)]
+pub struct SecretVersionListing {
+    pub id: i32,
+    pub version_tag: String,
+    pub sha256sum: Option<String>,
+    pub deleted: bool,
+    pub expire_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub destroyed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(FromRow, Debug)]
@@ -223,11 +788,76 @@ pub struct SecretVersion {
     pub secret_id: i32,
     pub version_tag: String,
     pub sha256sum: Option<String>,
-    pub encrypted_secret: String,
-    pub dek_id: i32,
+    pub encrypted_secret: Vec<u8>,
+    /// `None` once the version has been destroyed.
+    pub dek_id: Option<i32>,
     pub deleted: bool,
     pub expire_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
+    pub destroyed_at: Option<DateTime<Utc>>,
+}
+
+impl SecretVersion {
+    /// The version's DEK id, or `AppError::Destroyed` if the version has been destroyed.
+    pub fn dek_id(&self) -> Result<i32, AppError> {
+        self.dek_id.ok_or(AppError::Destroyed)
+    }
+}
+
+/// Parameters of the Shamir split, recorded when the vault is initialized.
+#[derive(FromRow, Debug)]
+pub struct SealConfig {
+    pub threshold: i32,
+    pub share_count: i32,
+    /// HMAC of a fixed label under the master key, to recognise a correct reconstruction.
+    pub key_check: Vec<u8>,
+}
+
+#[derive(FromRow, Debug)]
+pub struct ClientKey {
+    pub key_id: String,
+    pub name: String,
+    pub algorithm: SignatureAlgorithm,
+    /// PEM-encoded SubjectPublicKeyInfo or hex-encoded public key, as registered.
+    pub public_key: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked: bool,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClientKey {
+    /// Whether requests signed with this key are accepted at `now`.
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct SessionToken {
+    pub id: i32,
+    pub client_name: String,
+    pub key_id: Option<String>,
+    pub is_root: bool,
+    pub capabilities: Option<Vec<Capability>>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked: bool,
+}
+
+impl SessionToken {
+    /// Whether requests bearing this token are accepted at `now`.
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked && now < self.expires_at
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct Policy {
+    pub id: i32,
+    pub client_name: String,
+    pub capabilities: Vec<Capability>,
+    pub pattern: String,
+    pub created_at: DateTime<Utc>,
 }
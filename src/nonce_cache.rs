@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Nonces of recently accepted signed requests, keyed by client name and nonce, with the
+/// time in milliseconds since the epoch after which their timestamp is outside the recv
+/// window and the nonce may be forgotten.
+pub struct NonceCache {
+    entries: Mutex<HashMap<(String, String), i64>>,
+}
+
+impl NonceCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records a nonce, returning `false` if it was already recorded and has not expired.
+    pub fn insert(&self, client: &str, nonce: &str, expires_at_ms: i64, now_ms: i64) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let key = (client.to_string(), nonce.to_string());
+        if entries
+            .get(&key)
+            .is_some_and(|&expires_at| expires_at >= now_ms)
+        {
+            return false;
+        }
+        entries.insert(key, expires_at_ms);
+        true
+    }
+
+    /// Drops every expired nonce.
+    pub fn prune(&self, now_ms: i64) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|_, expires_at| *expires_at >= now_ms);
+    }
+}
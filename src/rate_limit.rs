@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The classes of routes that are rate limited separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    Read,
+    Write,
+    ConnectionAdmin,
+}
+
+impl RouteClass {
+    /// `/v1/vault-connections` routes are connection admin, other `GET` requests are reads
+    /// and everything else is a write.
+    pub fn of(method: &axum::http::Method, path: &str) -> Self {
+        if path.starts_with("/v1/vault-connections") {
+            RouteClass::ConnectionAdmin
+        } else if method == axum::http::Method::GET {
+            RouteClass::Read
+        } else {
+            RouteClass::Write
+        }
+    }
+}
+
+/// Sustained rate and burst size of a token bucket. A rate of zero disables the limit.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub per_sec: f64,
+    pub burst: f64,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Token buckets of one rate limit, keyed by client name or IP address.
+struct TokenBuckets {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    allowed: AtomicU64,
+    throttled: AtomicU64,
+}
+
+impl TokenBuckets {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+            allowed: AtomicU64::new(0),
+            throttled: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a token from `key`'s bucket, or returns how long until one is available.
+    fn acquire(&self, key: &str) -> Result<(), Duration> {
+        self.acquire_at(key, Instant::now())
+    }
+
+    fn acquire_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.limit.per_sec <= 0.0 {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key.to_string()).or_insert(TokenBucket {
+            tokens: self.limit.burst,
+            updated_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.limit.per_sec).min(self.limit.burst);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            self.allowed.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        } else {
+            self.throttled.fetch_add(1, Ordering::Relaxed);
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.limit.per_sec,
+            ))
+        }
+    }
+
+    /// Drops buckets that have refilled completely, which behave like new ones.
+    fn prune(&self) {
+        if self.limit.per_sec <= 0.0 {
+            return;
+        }
+        let refill_time = Duration::from_secs_f64(self.limit.burst / self.limit.per_sec);
+        self.buckets
+            .lock()
+            .unwrap()
+            .retain(|_, bucket| bucket.updated_at.elapsed() < refill_time);
+    }
+
+    fn stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            allowed: self.allowed.load(Ordering::Relaxed),
+            throttled: self.throttled.load(Ordering::Relaxed),
+            tracked: self.buckets.lock().unwrap().len(),
+        }
+    }
+}
+
+pub struct RateLimitStats {
+    pub allowed: u64,
+    pub throttled: u64,
+    pub tracked: usize,
+}
+
+/// Per-client token buckets for each route class, per-IP token buckets applied before
+/// authentication, and a cap on each client's concurrent requests.
+pub struct RateLimiter {
+    reads: TokenBuckets,
+    writes: TokenBuckets,
+    connection_admin: TokenBuckets,
+    ips: TokenBuckets,
+    max_concurrent_requests: usize,
+    in_flight: Mutex<HashMap<String, usize>>,
+    concurrency_rejected: AtomicU64,
+}
+
+impl RateLimiter {
+    pub fn new(
+        reads: RateLimit,
+        writes: RateLimit,
+        connection_admin: RateLimit,
+        ips: RateLimit,
+        max_concurrent_requests: usize,
+    ) -> Self {
+        Self {
+            reads: TokenBuckets::new(reads),
+            writes: TokenBuckets::new(writes),
+            connection_admin: TokenBuckets::new(connection_admin),
+            ips: TokenBuckets::new(ips),
+            max_concurrent_requests,
+            in_flight: Mutex::new(HashMap::new()),
+            concurrency_rejected: AtomicU64::new(0),
+        }
+    }
+
+    pub fn acquire_client(&self, client: &str, class: RouteClass) -> Result<(), Duration> {
+        self.buckets(class).acquire(client)
+    }
+
+    pub fn acquire_ip(&self, ip: &str) -> Result<(), Duration> {
+        self.ips.acquire(ip)
+    }
+
+    /// Counts a request against the client's concurrency quota until the returned guard
+    /// is dropped, or returns `None` if the client is at its quota. A quota of zero
+    /// disables the limit.
+    pub fn start_request(&self, client: &str) -> Option<InFlightGuard<'_>> {
+        if self.max_concurrent_requests > 0 {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let count = in_flight.entry(client.to_string()).or_insert(0);
+            if *count >= self.max_concurrent_requests {
+                self.concurrency_rejected.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            *count += 1;
+        }
+
+        Some(InFlightGuard {
+            limiter: self,
+            client: client.to_string(),
+        })
+    }
+
+    pub fn prune(&self) {
+        self.reads.prune();
+        self.writes.prune();
+        self.connection_admin.prune();
+        self.ips.prune();
+    }
+
+    pub fn stats(&self, class: RouteClass) -> RateLimitStats {
+        self.buckets(class).stats()
+    }
+
+    pub fn ip_stats(&self) -> RateLimitStats {
+        self.ips.stats()
+    }
+
+    pub fn concurrency_rejected(&self) -> u64 {
+        self.concurrency_rejected.load(Ordering::Relaxed)
+    }
+
+    fn buckets(&self, class: RouteClass) -> &TokenBuckets {
+        match class {
+            RouteClass::Read => &self.reads,
+            RouteClass::Write => &self.writes,
+            RouteClass::ConnectionAdmin => &self.connection_admin,
+        }
+    }
+}
+
+pub struct InFlightGuard<'a> {
+    limiter: &'a RateLimiter,
+    client: String,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if self.limiter.max_concurrent_requests == 0 {
+            return;
+        }
+        let mut in_flight = self.limiter.in_flight.lock().unwrap();
+        if let Some(count) = in_flight.get_mut(&self.client) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(&self.client);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(per_sec: f64, burst: f64) -> TokenBuckets {
+        TokenBuckets::new(RateLimit { per_sec, burst })
+    }
+
+    fn assert_retry_after(result: Result<(), Duration>, expected_secs: f64) {
+        let retry_after = result.expect_err("request should be throttled");
+        assert!(
+            (retry_after.as_secs_f64() - expected_secs).abs() < 1e-6,
+            "retry after {:?}, expected {}s",
+            retry_after,
+            expected_secs
+        );
+    }
+
+    #[test]
+    fn allows_a_burst_then_throttles() {
+        let buckets = buckets(2.0, 3.0);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(buckets.acquire_at("client", now).is_ok());
+        }
+        assert_retry_after(buckets.acquire_at("client", now), 0.5);
+        assert_eq!(buckets.stats().allowed, 3);
+        assert_eq!(buckets.stats().throttled, 1);
+    }
+
+    #[test]
+    fn refills_at_the_configured_rate() {
+        let buckets = buckets(2.0, 1.0);
+        let now = Instant::now();
+
+        assert!(buckets.acquire_at("client", now).is_ok());
+        assert_retry_after(
+            buckets.acquire_at("client", now + Duration::from_millis(200)),
+            0.3,
+        );
+        assert!(
+            buckets
+                .acquire_at("client", now + Duration::from_millis(600))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn refill_is_capped_at_the_burst() {
+        let buckets = buckets(10.0, 2.0);
+        let now = Instant::now();
+        assert!(buckets.acquire_at("client", now).is_ok());
+
+        let later = now + Duration::from_secs(60);
+        assert!(buckets.acquire_at("client", later).is_ok());
+        assert!(buckets.acquire_at("client", later).is_ok());
+        assert_retry_after(buckets.acquire_at("client", later), 0.1);
+    }
+
+    #[test]
+    fn keys_have_separate_buckets() {
+        let buckets = buckets(1.0, 1.0);
+        let now = Instant::now();
+
+        assert!(buckets.acquire_at("a", now).is_ok());
+        assert!(buckets.acquire_at("a", now).is_err());
+        assert!(buckets.acquire_at("b", now).is_ok());
+    }
+
+    #[test]
+    fn zero_rate_disables_the_limit() {
+        let buckets = buckets(0.0, 0.0);
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(buckets.acquire_at("client", now).is_ok());
+        }
+        buckets.prune();
+    }
+}
@@ -4,6 +4,7 @@ use std::sync::OnceLock;
 pub static PUBLIC_ID_REGEX: OnceLock<Regex> = OnceLock::new();
 pub static VERSION_TAG_REGEX: OnceLock<Regex> = OnceLock::new();
 pub static SECRET_NAME_REGEX: OnceLock<Regex> = OnceLock::new();
+pub static NONCE_REGEX: OnceLock<Regex> = OnceLock::new();
 pub static ENDING_NUMBER_REGEX: OnceLock<Regex> = OnceLock::new();
 
 pub fn get_public_id_regex() -> &'static Regex {
@@ -21,6 +22,10 @@ pub fn get_secret_name_regex() -> &'static Regex {
         .get_or_init(|| Regex::new(r"^[a-zA-Z0-9]([a-zA-Z0-9_/.-]*[a-zA-Z0-9])?$").unwrap())
 }
 
+pub fn get_nonce_regex() -> &'static Regex {
+    NONCE_REGEX.get_or_init(|| Regex::new(r"^[a-zA-Z0-9_-]{16,64}$").unwrap())
+}
+
 pub fn get_ending_number_regex() -> &'static Regex {
     ENDING_NUMBER_REGEX.get_or_init(|| Regex::new(r"[0-9]+$").unwrap())
 }
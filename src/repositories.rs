@@ -1,4 +1,10 @@
+pub mod client_keys;
 pub mod connections;
 pub mod dek;
 pub mod kek;
+pub mod nonces;
+pub mod policies;
+pub mod seal;
 pub mod secrets;
+pub mod session_tokens;
+pub mod shred_records;
@@ -0,0 +1,71 @@
+use crate::errors::AppError;
+use crate::models::{ClientKey, SignatureAlgorithm};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct ClientKeyRepository;
+
+impl ClientKeyRepository {
+    pub async fn create_client_key(
+        db: &PgPool,
+        key_id: &str,
+        name: &str,
+        algorithm: SignatureAlgorithm,
+        public_key: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ClientKey, AppError> {
+        let key = sqlx::query_as(
+            r#"
+            INSERT INTO client_keys (key_id, name, algorithm, public_key, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(key_id)
+        .bind(name)
+        .bind(algorithm)
+        .bind(public_key)
+        .bind(expires_at)
+        .fetch_one(db)
+        .await?;
+        Ok(key)
+    }
+
+    pub async fn get_client_key_by_key_id(
+        db: &PgPool,
+        key_id: &str,
+    ) -> Result<Option<ClientKey>, AppError> {
+        let key = sqlx::query_as("SELECT * FROM client_keys WHERE key_id = $1")
+            .bind(key_id)
+            .fetch_optional(db)
+            .await?;
+        Ok(key)
+    }
+
+    pub async fn get_client_keys(db: &PgPool) -> Result<Vec<ClientKey>, AppError> {
+        let keys = sqlx::query_as("SELECT * FROM client_keys ORDER BY id")
+            .fetch_all(db)
+            .await?;
+        Ok(keys)
+    }
+
+    /// Revokes a key, returning it, or `None` if no key has that ID.
+    pub async fn revoke_client_key(
+        db: &PgPool,
+        key_id: &str,
+    ) -> Result<Option<ClientKey>, AppError> {
+        let key = sqlx::query_as(
+            r#"
+            UPDATE client_keys
+            SET revoked = true, revoked_at = COALESCE(revoked_at, $1)
+            WHERE key_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(key_id)
+        .fetch_optional(db)
+        .await?;
+        Ok(key)
+    }
+}
@@ -9,7 +9,7 @@ impl ConnectionRepository {
         tx: &mut Transaction<'_, Postgres>,
         payload: &CreateVaultConnectionRequest,
         sha256sum: &str,
-        encrypted_config: &str,
+        encrypted_config: &[u8],
         dek_id: i32,
     ) -> Result<VaultConnection, AppError> {
         let new_connection: VaultConnection = sqlx::query_as(
@@ -27,7 +27,7 @@ impl ConnectionRepository {
             .bind(payload.ttl)
             .fetch_one(&mut **tx)
             .await
-            .map_err(|db_err| AppError::from(db_err))?;
+            .map_err(AppError::from)?;
 
         Ok(new_connection)
     }
@@ -43,6 +43,24 @@ impl ConnectionRepository {
         Ok(connection)
     }
 
+    pub async fn get_latest_vault_connection(
+        db: &sqlx::PgPool,
+        integration_type: &str,
+    ) -> Result<Option<VaultConnection>, AppError> {
+        let connection = sqlx::query_as(
+            r#"
+            SELECT * FROM vault_connections
+            WHERE integration_type = $1
+            ORDER BY updated_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(integration_type)
+        .fetch_optional(db)
+        .await?;
+        Ok(connection)
+    }
+
     pub async fn get_vault_connection_by_id(
         db: &sqlx::PgPool,
         id: i32,
@@ -57,7 +75,7 @@ impl ConnectionRepository {
     pub async fn update_vault_connection(
         tx: &mut Transaction<'_, Postgres>,
         public_id: &str,
-        encrypted_config: Option<&str>,
+        encrypted_config: Option<&[u8]>,
         sha256sum: Option<&str>,
         dek_id: Option<i32>,
         ttl: Option<i32>,
@@ -99,4 +117,81 @@ impl ConnectionRepository {
             .await?;
         Ok(result.rows_affected())
     }
+
+    /// Locks a batch of connections whose DEK predates encryption contexts.
+    pub async fn get_legacy_vault_connections_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+    ) -> Result<Vec<VaultConnection>, AppError> {
+        let connections = sqlx::query_as(
+            r#"
+            SELECT vc.* FROM vault_connections vc
+            JOIN data_encryption_keys dek ON dek.id = vc.dek_id
+            WHERE dek.encryption_context IS NULL
+            ORDER BY vc.id
+            LIMIT $1
+            FOR UPDATE OF vc SKIP LOCKED
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(connections)
+    }
+
+    pub async fn update_vault_connection_ciphertext(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i32,
+        encrypted_config: &[u8],
+        dek_id: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE vault_connections
+            SET encrypted_config = $1, dek_id = $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(encrypted_config)
+        .bind(dek_id)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Locks a batch of connections whose fingerprint predates HMAC fingerprints.
+    pub async fn get_legacy_fingerprint_connections_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        fingerprint_prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<VaultConnection>, AppError> {
+        let connections = sqlx::query_as(
+            r#"
+            SELECT * FROM vault_connections
+            WHERE NOT starts_with(sha256sum, $1)
+            ORDER BY id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(fingerprint_prefix)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(connections)
+    }
+
+    pub async fn update_vault_connection_fingerprint(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i32,
+        fingerprint: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE vault_connections SET sha256sum = $1 WHERE id = $2")
+            .bind(fingerprint)
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
+use crate::crypto::{Algorithm, EncryptionContext};
 use crate::errors::AppError;
 use crate::models::DataEncryptionKey;
+use sqlx::types::Json;
 use sqlx::{PgPool, Postgres, Transaction};
 
 pub struct DekRepository;
@@ -8,19 +10,22 @@ impl DekRepository {
     pub async fn create_dek(
         tx: &mut Transaction<'_, Postgres>,
         kek_id: i32,
-        encrypted_key: String,
+        encrypted_key: &[u8],
+        algorithm: Algorithm,
+        encryption_context: &EncryptionContext,
     ) -> Result<DataEncryptionKey, AppError> {
         let new_dek: DataEncryptionKey = sqlx::query_as(
             r#"
-            INSERT INTO data_encryption_keys (key_id, kek_id, encrypted_key, algo)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO data_encryption_keys (key_id, kek_id, encrypted_key, algo, encryption_context)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *
             "#,
         )
         .bind(uuid::Uuid::new_v4().to_string())
         .bind(kek_id)
         .bind(encrypted_key)
-        .bind("AES-256-GCM")
+        .bind(algorithm.name())
+        .bind(Json(encryption_context))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -35,4 +40,64 @@ impl DekRepository {
                 .await?;
         Ok(dek)
     }
+
+    pub async fn count_deks_by_kek(pool: &PgPool, kek_id: i32) -> Result<i64, AppError> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM data_encryption_keys WHERE kek_id = $1")
+                .bind(kek_id)
+                .fetch_one(pool)
+                .await?;
+        Ok(count)
+    }
+
+    /// Locks a batch of DEKs wrapped under `kek_id`, skipping rows locked by other workers.
+    pub async fn get_deks_by_kek_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        kek_id: i32,
+        limit: i64,
+    ) -> Result<Vec<DataEncryptionKey>, AppError> {
+        let deks = sqlx::query_as(
+            r#"
+            SELECT * FROM data_encryption_keys
+            WHERE kek_id = $1
+            ORDER BY id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(kek_id)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(deks)
+    }
+
+    pub async fn update_dek_wrapping(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i32,
+        kek_id: i32,
+        encrypted_key: &[u8],
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE data_encryption_keys SET kek_id = $1, encrypted_key = $2 WHERE id = $3",
+        )
+        .bind(kek_id)
+        .bind(encrypted_key)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes a DEK, returning the deleted row.
+    pub async fn delete_dek(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i32,
+    ) -> Result<DataEncryptionKey, AppError> {
+        let dek = sqlx::query_as("DELETE FROM data_encryption_keys WHERE id = $1 RETURNING *")
+            .bind(id)
+            .fetch_one(&mut **tx)
+            .await?;
+        Ok(dek)
+    }
 }
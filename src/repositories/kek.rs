@@ -1,26 +1,89 @@
 use crate::errors::AppError;
-use crate::models::KeyEncryptionKey;
-use sqlx::{PgPool, Postgres, Transaction};
+use crate::models::{KekState, KekUsage, KeyEncryptionKey};
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
 
 pub struct KekRepository;
 
 impl KekRepository {
-    pub async fn get_random_kek(
+    /// Picks the KEK to wrap a new DEK under: an active KEK chosen at random with
+    /// probability proportional to its weight. KEKs with weight 0 are never chosen.
+    pub async fn select_active_kek(
         tx: &mut Transaction<'_, Postgres>,
     ) -> Result<KeyEncryptionKey, AppError> {
-        let kek: Option<KeyEncryptionKey> =
-            sqlx::query_as("SELECT * FROM key_encryption_keys ORDER BY RANDOM()")
-                .fetch_optional(&mut **tx)
-                .await?;
+        // Weighted sampling: the smallest -ln(U)/weight wins with probability weight/sum.
+        let kek: Option<KeyEncryptionKey> = sqlx::query_as(
+            r#"
+            SELECT * FROM key_encryption_keys
+            WHERE state = $1 AND weight > 0
+            ORDER BY -ln(1.0 - random()) / weight
+            LIMIT 1
+            "#,
+        )
+        .bind(KekState::Active)
+        .fetch_optional(&mut **tx)
+        .await?;
 
         match kek {
             None => Err(AppError::KmsError(
-                "No Key Encryption Keys available".to_string(),
+                "No active Key Encryption Keys available".to_string(),
             )),
-            Some(kek) => Ok(kek)
+            Some(kek) => Ok(kek),
         }
     }
 
+    pub async fn count_selectable_keks(
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM key_encryption_keys WHERE state = $1 AND weight > 0",
+        )
+        .bind(KekState::Active)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(count)
+    }
+
+    pub async fn create_kek(
+        tx: &mut Transaction<'_, Postgres>,
+        backend: &str,
+        kms_key: &str,
+        weight: i32,
+        verified_at: DateTime<Utc>,
+    ) -> Result<KeyEncryptionKey, AppError> {
+        let kek = sqlx::query_as(
+            r#"
+            INSERT INTO key_encryption_keys (backend, kms_key, weight, verified_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(backend)
+        .bind(kms_key)
+        .bind(weight)
+        .bind(verified_at)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(kek)
+    }
+
+    pub async fn find_kek<'e, E>(
+        executor: E,
+        backend: &str,
+        kms_key: &str,
+    ) -> Result<Option<KeyEncryptionKey>, AppError>
+    where
+        E: PgExecutor<'e>,
+    {
+        let kek =
+            sqlx::query_as("SELECT * FROM key_encryption_keys WHERE backend = $1 AND kms_key = $2")
+                .bind(backend)
+                .bind(kms_key)
+                .fetch_optional(executor)
+                .await?;
+        Ok(kek)
+    }
+
     pub async fn get_kek_by_id(pool: &PgPool, id: i32) -> Result<KeyEncryptionKey, AppError> {
         let kek: KeyEncryptionKey =
             sqlx::query_as("SELECT * FROM key_encryption_keys WHERE id = $1")
@@ -29,4 +92,117 @@ impl KekRepository {
                 .await?;
         Ok(kek)
     }
+
+    pub async fn get_kek_by_id_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i32,
+    ) -> Result<Option<KeyEncryptionKey>, AppError> {
+        let kek = sqlx::query_as("SELECT * FROM key_encryption_keys WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?;
+        Ok(kek)
+    }
+
+    /// Lists every KEK with the number of DEKs currently wrapped under it.
+    pub async fn get_kek_usages(pool: &PgPool) -> Result<Vec<KekUsage>, AppError> {
+        let usages = sqlx::query_as(
+            r#"
+            SELECT k.*, COUNT(d.id) AS dek_count
+            FROM key_encryption_keys k
+            LEFT JOIN data_encryption_keys d ON d.kek_id = k.id
+            GROUP BY k.id
+            ORDER BY k.id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(usages)
+    }
+
+    pub async fn get_kek_usage_by_id<'e, E>(
+        executor: E,
+        id: i32,
+    ) -> Result<Option<KekUsage>, AppError>
+    where
+        E: PgExecutor<'e>,
+    {
+        let usage = sqlx::query_as(
+            r#"
+            SELECT k.*, COUNT(d.id) AS dek_count
+            FROM key_encryption_keys k
+            LEFT JOIN data_encryption_keys d ON d.kek_id = k.id
+            WHERE k.id = $1
+            GROUP BY k.id
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await?;
+        Ok(usage)
+    }
+
+    pub async fn get_keks_by_state(
+        pool: &PgPool,
+        state: KekState,
+    ) -> Result<Vec<KeyEncryptionKey>, AppError> {
+        let keks = sqlx::query_as("SELECT * FROM key_encryption_keys WHERE state = $1 ORDER BY id")
+            .bind(state)
+            .fetch_all(pool)
+            .await?;
+        Ok(keks)
+    }
+
+    pub async fn update_kek_state<'e, E>(
+        executor: E,
+        id: i32,
+        state: KekState,
+    ) -> Result<(), AppError>
+    where
+        E: PgExecutor<'e>,
+    {
+        sqlx::query("UPDATE key_encryption_keys SET state = $1 WHERE id = $2")
+            .bind(state)
+            .bind(id)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_kek_weight(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i32,
+        weight: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE key_encryption_keys SET weight = $1 WHERE id = $2")
+            .bind(weight)
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_kek_verified_at<'e, E>(
+        executor: E,
+        id: i32,
+        verified_at: DateTime<Utc>,
+    ) -> Result<(), AppError>
+    where
+        E: PgExecutor<'e>,
+    {
+        sqlx::query("UPDATE key_encryption_keys SET verified_at = $1 WHERE id = $2")
+            .bind(verified_at)
+            .bind(id)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_kek(tx: &mut Transaction<'_, Postgres>, id: i32) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM key_encryption_keys WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
 }
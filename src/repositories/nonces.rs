@@ -0,0 +1,38 @@
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct NonceRepository;
+
+impl NonceRepository {
+    /// Records a nonce, returning `false` if it was already recorded and has not expired.
+    pub async fn insert_nonce(
+        db: &PgPool,
+        client_name: &str,
+        nonce: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO request_nonces (client_name, nonce, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (client_name, nonce) DO UPDATE
+            SET expires_at = EXCLUDED.expires_at
+            WHERE request_nonces.expires_at < now()
+            "#,
+        )
+        .bind(client_name)
+        .bind(nonce)
+        .bind(expires_at)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete_expired_nonces(db: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM request_nonces WHERE expires_at < now()")
+            .execute(db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,54 @@
+use crate::errors::AppError;
+use crate::models::{Capability, Policy};
+use sqlx::PgPool;
+
+pub struct PolicyRepository;
+
+impl PolicyRepository {
+    pub async fn create_policy(
+        db: &PgPool,
+        client_name: &str,
+        capabilities: &[Capability],
+        pattern: &str,
+    ) -> Result<Policy, AppError> {
+        let policy = sqlx::query_as(
+            r#"
+            INSERT INTO policies (client_name, capabilities, pattern)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(client_name)
+        .bind(capabilities)
+        .bind(pattern)
+        .fetch_one(db)
+        .await?;
+        Ok(policy)
+    }
+
+    pub async fn get_policies(db: &PgPool) -> Result<Vec<Policy>, AppError> {
+        let policies = sqlx::query_as("SELECT * FROM policies ORDER BY id")
+            .fetch_all(db)
+            .await?;
+        Ok(policies)
+    }
+
+    pub async fn get_policies_by_client_name(
+        db: &PgPool,
+        client_name: &str,
+    ) -> Result<Vec<Policy>, AppError> {
+        let policies = sqlx::query_as("SELECT * FROM policies WHERE client_name = $1")
+            .bind(client_name)
+            .fetch_all(db)
+            .await?;
+        Ok(policies)
+    }
+
+    pub async fn delete_policy(db: &PgPool, id: i32) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM policies WHERE id = $1")
+            .bind(id)
+            .execute(db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
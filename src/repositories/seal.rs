@@ -0,0 +1,38 @@
+use crate::errors::AppError;
+use crate::models::SealConfig;
+use sqlx::{PgExecutor, Postgres, Transaction};
+
+pub struct SealRepository;
+
+impl SealRepository {
+    pub async fn get_seal_config<'e, E>(executor: E) -> Result<Option<SealConfig>, AppError>
+    where
+        E: PgExecutor<'e>,
+    {
+        let config = sqlx::query_as("SELECT * FROM seal_config")
+            .fetch_optional(executor)
+            .await?;
+        Ok(config)
+    }
+
+    pub async fn create_seal_config(
+        tx: &mut Transaction<'_, Postgres>,
+        threshold: i32,
+        share_count: i32,
+        key_check: &[u8],
+    ) -> Result<SealConfig, AppError> {
+        let config = sqlx::query_as(
+            r#"
+            INSERT INTO seal_config (threshold, share_count, key_check)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(threshold)
+        .bind(share_count)
+        .bind(key_check)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(config)
+    }
+}
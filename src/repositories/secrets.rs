@@ -1,7 +1,9 @@
 use crate::errors::AppError;
-use crate::models::{Secret, SecretVersion};
+use crate::models::{
+    ListSecretsQuery, Secret, SecretListing, SecretVersion, SecretVersionListing, SortOrder,
+};
 use chrono::{DateTime, Utc};
-use sqlx::{PgExecutor, Postgres, Transaction};
+use sqlx::{PgExecutor, Postgres, QueryBuilder, Transaction};
 
 pub struct SecretRepository;
 
@@ -24,7 +26,7 @@ impl SecretRepository {
         .bind(version_tag)
         .fetch_one(&mut **tx)
         .await
-        .map_err(|db_err| AppError::from(db_err))?;
+        .map_err(AppError::from)?;
         Ok(secret)
     }
 
@@ -32,16 +34,168 @@ impl SecretRepository {
         db: &sqlx::PgPool,
         name: &str,
     ) -> Result<Option<Secret>, AppError> {
-        let secret = sqlx::query_as("SELECT * FROM secrets WHERE name = $1")
+        let secret = sqlx::query_as("SELECT * FROM secrets WHERE name = $1 AND deleted_at IS NULL")
             .bind(name)
             .fetch_optional(db)
             .await?;
         Ok(secret)
     }
 
+    /// Returns the next segment of every secret name under `prefix`, and whether it is a
+    /// folder (has further segments) rather than a secret.
+    pub async fn get_folder_children(
+        db: &sqlx::PgPool,
+        prefix: &str,
+    ) -> Result<Vec<(String, bool)>, AppError> {
+        let children = sqlx::query_as(
+            r#"
+            SELECT DISTINCT split_part(substr(name, $2), '/', 1) AS child,
+                   strpos(substr(name, $2), '/') > 0 AS is_folder
+            FROM secrets
+            WHERE name LIKE $1 AND deleted_at IS NULL
+            ORDER BY child, is_folder
+            "#,
+        )
+        .bind(like_prefix(prefix))
+        .bind(prefix.len() as i32 + 1)
+        .fetch_all(db)
+        .await?;
+        Ok(children)
+    }
+
+    pub async fn get_secrets_by_prefix_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        prefix: &str,
+    ) -> Result<Vec<Secret>, AppError> {
+        let secrets =
+            sqlx::query_as("SELECT * FROM secrets WHERE name LIKE $1 ORDER BY name FOR UPDATE")
+                .bind(like_prefix(prefix))
+                .fetch_all(&mut **tx)
+                .await?;
+        Ok(secrets)
+    }
+
+    /// Lists secrets matching `query` in `created_at` order, starting after the
+    /// `(created_at, id)` position of the previous page.
+    /// List secrets matching `query`, and any of the `readable` policy patterns unless
+    /// it is `None`, ordered and paged by creation time
+    pub async fn list_secrets(
+        db: &sqlx::PgPool,
+        query: &ListSecretsQuery,
+        readable: Option<&[String]>,
+        after: Option<(DateTime<Utc>, i32)>,
+        limit: i64,
+    ) -> Result<Vec<SecretListing>, AppError> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT s.id, s.name, s.current_version, s.previous_version,
+                   vc.public_id AS vault_connection, s.expire_at, s.created_at, s.updated_at,
+                   s.deleted_at
+            FROM secrets s
+            LEFT JOIN vault_connections vc ON vc.id = s.vault_connection_id
+            "#,
+        );
+        if query.deleted {
+            builder.push(" WHERE s.deleted_at IS NOT NULL");
+        } else {
+            builder.push(" WHERE s.deleted_at IS NULL");
+        }
+        if let Some(prefix) = &query.prefix {
+            builder
+                .push(" AND s.name LIKE ")
+                .push_bind(like_prefix(prefix));
+        }
+        if let Some(patterns) = readable {
+            let patterns: Vec<String> = patterns.iter().map(|p| like_pattern(p)).collect();
+            builder
+                .push(" AND s.name LIKE ANY(")
+                .push_bind(patterns)
+                .push(")");
+        }
+        if let Some(created_after) = query.created_after {
+            builder
+                .push(" AND s.created_at > ")
+                .push_bind(created_after);
+        }
+        if let Some(created_before) = query.created_before {
+            builder
+                .push(" AND s.created_at < ")
+                .push_bind(created_before);
+        }
+        if let Some(updated_after) = query.updated_after {
+            builder
+                .push(" AND s.updated_at > ")
+                .push_bind(updated_after);
+        }
+        if let Some(updated_before) = query.updated_before {
+            builder
+                .push(" AND s.updated_at < ")
+                .push_bind(updated_before);
+        }
+        match query.proxied {
+            Some(true) => {
+                builder.push(" AND s.vault_connection_id IS NOT NULL");
+            }
+            Some(false) => {
+                builder.push(" AND s.vault_connection_id IS NULL");
+            }
+            None => {}
+        }
+
+        // Written so the bound on `created_at` alone can use idx_secrets_created_at_desc.
+        let (comparison, direction) = match query.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+        if let Some((created_at, id)) = after {
+            builder
+                .push(format!(" AND s.created_at {}= ", comparison))
+                .push_bind(created_at)
+                .push(format!(" AND (s.created_at {} ", comparison))
+                .push_bind(created_at)
+                .push(format!(" OR s.id {} ", comparison))
+                .push_bind(id)
+                .push(")");
+        }
+        builder
+            .push(format!(
+                " ORDER BY s.created_at {0}, s.id {0} LIMIT ",
+                direction
+            ))
+            .push_bind(limit);
+
+        let secrets = builder.build_query_as().fetch_all(db).await?;
+        Ok(secrets)
+    }
+
+    pub async fn get_secret_by_id<'e, E>(executor: E, id: i32) -> Result<Option<Secret>, AppError>
+    where
+        E: PgExecutor<'e>,
+    {
+        let secret = sqlx::query_as("SELECT * FROM secrets WHERE id = $1")
+            .bind(id)
+            .fetch_optional(executor)
+            .await?;
+        Ok(secret)
+    }
+
     pub async fn get_secret_by_name_for_update(
         tx: &mut Transaction<'_, Postgres>,
         name: &str,
+    ) -> Result<Option<Secret>, AppError> {
+        let secret = sqlx::query_as(
+            "SELECT * FROM secrets WHERE name = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(name)
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(secret)
+    }
+
+    /// Locks a secret whether or not it has been soft-deleted.
+    pub async fn get_any_secret_by_name_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
     ) -> Result<Option<Secret>, AppError> {
         let secret = sqlx::query_as("SELECT * FROM secrets WHERE name = $1 FOR UPDATE")
             .bind(name)
@@ -50,12 +204,70 @@ impl SecretRepository {
         Ok(secret)
     }
 
+    pub async fn set_secret_deleted_at(
+        tx: &mut Transaction<'_, Postgres>,
+        secret_id: i32,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE secrets SET deleted_at = $1, updated_at = $2 WHERE id = $3")
+            .bind(deleted_at)
+            .bind(Utc::now())
+            .bind(secret_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Locks a batch of secrets soft-deleted before `deleted_before`.
+    pub async fn get_purgeable_secrets_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        deleted_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Secret>, AppError> {
+        let secrets = sqlx::query_as(
+            r#"
+            SELECT * FROM secrets
+            WHERE deleted_at < $1
+            ORDER BY id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(deleted_before)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(secrets)
+    }
+
+    /// Locks a batch of versions soft-deleted before `deleted_before`.
+    pub async fn get_purgeable_secret_versions_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        deleted_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SecretVersion>, AppError> {
+        let versions = sqlx::query_as(
+            r#"
+            SELECT * FROM secret_versions
+            WHERE deleted AND deleted_at < $1
+            ORDER BY id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(deleted_before)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(versions)
+    }
+
     pub async fn create_secret_version(
         tx: &mut Transaction<'_, Postgres>,
         secret_id: i32,
         version_tag: &str,
         sha256sum: &str,
-        encrypted_secret: &str,
+        encrypted_secret: &[u8],
         dek_id: i32,
     ) -> Result<SecretVersion, AppError> {
         let version = sqlx::query_as(
@@ -72,7 +284,7 @@ impl SecretRepository {
         .bind(dek_id)
         .fetch_one(&mut **tx)
         .await
-        .map_err(|db_err| AppError::from(db_err))?;
+        .map_err(AppError::from)?;
         Ok(version)
     }
 
@@ -94,6 +306,58 @@ impl SecretRepository {
         Ok(version)
     }
 
+    /// Lists a secret's versions newest first, starting after the version `after_id`.
+    pub async fn list_secret_versions(
+        db: &sqlx::PgPool,
+        secret_id: i32,
+        after_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<SecretVersionListing>, AppError> {
+        let versions = sqlx::query_as(
+            r#"
+            SELECT id, version_tag, sha256sum, deleted, expire_at, created_at, destroyed_at
+            FROM secret_versions
+            WHERE secret_id = $1 AND ($2::integer IS NULL OR id < $2)
+            ORDER BY id DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(secret_id)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await?;
+        Ok(versions)
+    }
+
+    pub async fn get_secret_version_by_tag_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        secret_id: i32,
+        tag: &str,
+    ) -> Result<Option<SecretVersion>, AppError> {
+        let version = sqlx::query_as(
+            "SELECT * FROM secret_versions WHERE secret_id = $1 AND version_tag = $2 FOR UPDATE",
+        )
+        .bind(secret_id)
+        .bind(tag)
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(version)
+    }
+
+    pub async fn get_secret_versions_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        secret_id: i32,
+    ) -> Result<Vec<SecretVersion>, AppError> {
+        let versions = sqlx::query_as(
+            "SELECT * FROM secret_versions WHERE secret_id = $1 ORDER BY id FOR UPDATE",
+        )
+        .bind(secret_id)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(versions)
+    }
+
     pub async fn update_secret_versions(
         tx: &mut Transaction<'_, Postgres>,
         secret_id: i32,
@@ -116,6 +380,29 @@ impl SecretRepository {
         Ok(())
     }
 
+    /// Sets the current version, which unlike `update_secret_versions` may be cleared.
+    pub async fn update_secret_current_version(
+        tx: &mut Transaction<'_, Postgres>,
+        secret_id: i32,
+        current_version: Option<&str>,
+        previous_version: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE secrets
+            SET current_version = $1, previous_version = $2, updated_at = $3
+            WHERE id = $4
+            "#,
+        )
+        .bind(current_version)
+        .bind(previous_version)
+        .bind(Utc::now())
+        .bind(secret_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_secret_proxied(
         tx: &mut Transaction<'_, Postgres>,
         secret_id: i32,
@@ -179,4 +466,186 @@ impl SecretRepository {
         .await?;
         Ok(())
     }
+
+    /// Locks a batch of versions whose DEK predates encryption contexts.
+    pub async fn get_legacy_secret_versions_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+    ) -> Result<Vec<SecretVersion>, AppError> {
+        let versions = sqlx::query_as(
+            r#"
+            SELECT sv.* FROM secret_versions sv
+            JOIN data_encryption_keys dek ON dek.id = sv.dek_id
+            WHERE dek.encryption_context IS NULL
+            ORDER BY sv.id
+            LIMIT $1
+            FOR UPDATE OF sv SKIP LOCKED
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(versions)
+    }
+
+    pub async fn update_secret_version_ciphertext(
+        tx: &mut Transaction<'_, Postgres>,
+        version_id: i32,
+        encrypted_secret: &[u8],
+        dek_id: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE secret_versions
+            SET encrypted_secret = $1, dek_id = $2, updated_at = $3
+            WHERE id = $4
+            "#,
+        )
+        .bind(encrypted_secret)
+        .bind(dek_id)
+        .bind(Utc::now())
+        .bind(version_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Locks a batch of versions whose fingerprint is missing or predates HMAC fingerprints.
+    pub async fn get_legacy_fingerprint_versions_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        fingerprint_prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<SecretVersion>, AppError> {
+        let versions = sqlx::query_as(
+            r#"
+            SELECT * FROM secret_versions
+            WHERE destroyed_at IS NULL
+              AND (sha256sum IS NULL OR NOT starts_with(sha256sum, $1))
+            ORDER BY id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(fingerprint_prefix)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await?;
+        Ok(versions)
+    }
+
+    pub async fn update_secret_version_fingerprint(
+        tx: &mut Transaction<'_, Postgres>,
+        version_id: i32,
+        fingerprint: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE secret_versions SET sha256sum = $1 WHERE id = $2")
+            .bind(fingerprint)
+            .bind(version_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_secret_version_deleted_at(
+        tx: &mut Transaction<'_, Postgres>,
+        version_id: i32,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE secret_versions
+            SET deleted = $1 IS NOT NULL, deleted_at = $1, updated_at = $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(deleted_at)
+        .bind(Utc::now())
+        .bind(version_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes a version row, whose DEK the caller must then delete.
+    pub async fn delete_secret_version(
+        tx: &mut Transaction<'_, Postgres>,
+        version_id: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM secret_versions WHERE id = $1")
+            .bind(version_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Clears a version's ciphertext and fingerprint and detaches its DEK, which the
+    /// caller must then delete.
+    pub async fn destroy_secret_version(
+        tx: &mut Transaction<'_, Postgres>,
+        version_id: i32,
+        destroyed_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE secret_versions
+            SET encrypted_secret = ''::bytea, sha256sum = NULL, dek_id = NULL,
+                destroyed_at = $1, updated_at = $1
+            WHERE id = $2
+            "#,
+        )
+        .bind(destroyed_at)
+        .bind(version_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_secret(
+        tx: &mut Transaction<'_, Postgres>,
+        secret_id: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM secrets WHERE id = $1")
+            .bind(secret_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+}
+
+/// A `LIKE` pattern matching names that start with `prefix`.
+fn like_prefix(prefix: &str) -> String {
+    let mut pattern = like_escape(prefix);
+    pattern.push('%');
+    pattern
+}
+
+/// The `LIKE` pattern equivalent to a policy pattern, where `*` matches any characters.
+fn like_pattern(pattern: &str) -> String {
+    like_escape(pattern).replace('*', "%")
+}
+
+fn like_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_prefix_escapes_wildcards() {
+        assert_eq!(like_prefix("payments/"), "payments/%");
+        assert_eq!(like_prefix("a_b%c\\"), "a\\_b\\%c\\\\%");
+    }
+
+    #[test]
+    fn like_pattern_translates_policy_stars() {
+        assert_eq!(like_pattern("*"), "%");
+        assert_eq!(like_pattern("payments/*"), "payments/%");
+        assert_eq!(like_pattern("*/api_key"), "%/api\\_key");
+        assert_eq!(like_pattern("100%"), "100\\%");
+    }
 }
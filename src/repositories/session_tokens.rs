@@ -0,0 +1,90 @@
+use crate::errors::AppError;
+use crate::models::{Capability, SessionToken};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct SessionTokenRepository;
+
+impl SessionTokenRepository {
+    pub async fn create_session_token(
+        db: &PgPool,
+        token_hash: &str,
+        client_name: &str,
+        key_id: Option<&str>,
+        is_root: bool,
+        capabilities: Option<&[Capability]>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<SessionToken, AppError> {
+        let token = sqlx::query_as(
+            r#"
+            INSERT INTO session_tokens
+                (token_hash, client_name, key_id, is_root, capabilities, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(token_hash)
+        .bind(client_name)
+        .bind(key_id)
+        .bind(is_root)
+        .bind(capabilities)
+        .bind(expires_at)
+        .fetch_one(db)
+        .await?;
+        Ok(token)
+    }
+
+    pub async fn get_session_token_by_hash(
+        db: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<SessionToken>, AppError> {
+        let token = sqlx::query_as("SELECT * FROM session_tokens WHERE token_hash = $1")
+            .bind(token_hash)
+            .fetch_optional(db)
+            .await?;
+        Ok(token)
+    }
+
+    pub async fn revoke_session_token(db: &PgPool, token_hash: &str) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE session_tokens
+            SET revoked = true, revoked_at = $1
+            WHERE token_hash = $2 AND NOT revoked
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(token_hash)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Revokes every unexpired token issued to requests signed with a client key.
+    pub async fn revoke_session_tokens_by_key_id(
+        db: &PgPool,
+        key_id: &str,
+    ) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            UPDATE session_tokens
+            SET revoked = true, revoked_at = $1
+            WHERE key_id = $2 AND NOT revoked AND expires_at > $1
+            "#,
+        )
+        .bind(now)
+        .bind(key_id)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes expired and revoked tokens, which can no longer authenticate a request.
+    pub async fn delete_expired_session_tokens(db: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM session_tokens WHERE expires_at < now() OR revoked")
+            .execute(db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
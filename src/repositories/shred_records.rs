@@ -0,0 +1,29 @@
+use crate::errors::AppError;
+use crate::models::DataEncryptionKey;
+use sqlx::{Postgres, Transaction};
+
+pub struct ShredRecordRepository;
+
+impl ShredRecordRepository {
+    /// Records that `dek` was deleted to destroy a secret version.
+    pub async fn create_shred_record(
+        tx: &mut Transaction<'_, Postgres>,
+        secret_name: &str,
+        version_tag: &str,
+        dek: &DataEncryptionKey,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO shred_records (secret_name, version_tag, dek_key_id, kek_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(secret_name)
+        .bind(version_tag)
+        .bind(&dek.key_id)
+        .bind(dek.kek_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+}
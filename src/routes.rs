@@ -1,23 +1,33 @@
+use crate::handlers::auth::AuthHandler;
+use crate::handlers::client_keys::ClientKeyHandler;
 use crate::handlers::connections::ConnectionHandler;
+use crate::handlers::keks::KekHandler;
+use crate::handlers::policies::PolicyHandler;
 use crate::handlers::secrets::SecretHandler;
+use crate::handlers::sys::SysHandler;
 use crate::state::AppState;
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use std::sync::Arc;
 
 pub fn configure_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
-        .route("/v1/secrets", post(SecretHandler::create_secret))
-        .route("/v1/secrets/{name}", get(SecretHandler::get_secret))
         .route(
-            "/v1/secrets/{name}/versions",
-            post(SecretHandler::create_secret_version),
+            "/v1/secrets",
+            get(SecretHandler::list_secrets).post(SecretHandler::create_secret),
         )
         .route(
-            "/v1/secrets/{name}/versions/{tag}",
-            get(SecretHandler::get_secret_version),
+            "/v1/secrets/{*path}",
+            get(SecretHandler::get_secret_path)
+                .post(SecretHandler::post_secret_path)
+                .delete(SecretHandler::delete_secret_path),
+        )
+        .route("/v1/secret-folders", get(SecretHandler::list_root_folder))
+        .route(
+            "/v1/secret-folders/{*path}",
+            get(SecretHandler::list_folder).post(SecretHandler::post_folder_path),
         )
         .route(
             "/v1/vault-connections",
@@ -29,4 +39,34 @@ pub fn configure_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>>
                 .patch(ConnectionHandler::update_vault_connection)
                 .delete(ConnectionHandler::delete_vault_connection),
         )
+        .route(
+            "/v1/keks",
+            get(KekHandler::list_keks).post(KekHandler::create_kek),
+        )
+        .route(
+            "/v1/keks/{id}",
+            get(KekHandler::get_kek)
+                .patch(KekHandler::update_kek)
+                .delete(KekHandler::delete_kek),
+        )
+        .route("/v1/keks/{id}/verify", post(KekHandler::verify_kek))
+        .route(
+            "/v1/client-keys",
+            get(ClientKeyHandler::list_client_keys).post(ClientKeyHandler::create_client_key),
+        )
+        .route(
+            "/v1/client-keys/{key_id}/revoke",
+            post(ClientKeyHandler::revoke_client_key),
+        )
+        .route("/v1/auth/login", post(AuthHandler::login))
+        .route("/v1/auth/logout", post(AuthHandler::logout))
+        .route(
+            "/v1/policies",
+            get(PolicyHandler::list_policies).post(PolicyHandler::create_policy),
+        )
+        .route("/v1/policies/{id}", delete(PolicyHandler::delete_policy))
+        .route("/v1/sys/init", post(SysHandler::init))
+        .route("/v1/sys/unseal", post(SysHandler::unseal))
+        .route("/v1/sys/seal", post(SysHandler::seal))
+        .route("/v1/sys/seal-status", get(SysHandler::seal_status))
 }
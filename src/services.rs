@@ -1,2 +1,12 @@
+pub mod backfill;
+pub mod client_keys;
 pub mod connections;
+pub mod health;
+pub mod keks;
+pub mod policies;
+pub mod purge;
+pub mod replay;
+pub mod rotation;
+pub mod seal;
 pub mod secrets;
+pub mod session_tokens;
@@ -0,0 +1,256 @@
+use crate::services::connections::ConnectionService;
+use crate::{
+    crypto::{self, EncryptionContext},
+    errors::AppError,
+    repositories::{
+        connections::ConnectionRepository, dek::DekRepository, secrets::SecretRepository,
+    },
+    state::AppState,
+};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use zeroize::Zeroizing;
+
+pub struct BackfillService;
+
+impl BackfillService {
+    /// Spawn a background task that re-encrypts every payload written before encryption
+    /// contexts were introduced
+    pub fn spawn_encryption_context_backfill(
+        state: Arc<AppState>,
+        batch_size: i64,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            match Self::backfill_encryption_context(&state, batch_size).await {
+                Ok(backfilled) => info!(backfilled, "encryption context backfill completed"),
+                Err(e) => error!("Encryption context backfill failed: {}", e),
+            }
+        })
+    }
+
+    /// Spawn a background task that replaces every unkeyed SHA-256 fingerprint with an
+    /// HMAC fingerprint
+    pub fn spawn_fingerprint_backfill(state: Arc<AppState>, batch_size: i64) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            match Self::backfill_fingerprints(&state, batch_size).await {
+                Ok(backfilled) => info!(backfilled, "fingerprint backfill completed"),
+                Err(e) => error!("Fingerprint backfill failed: {}", e),
+            }
+        })
+    }
+
+    /// Re-encrypt legacy secret versions and vault connections under their encryption
+    /// context, replacing their DEKs
+    pub async fn backfill_encryption_context(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<usize, AppError> {
+        let mut backfilled = 0;
+
+        loop {
+            let count = Self::backfill_secret_versions_batch(state, batch_size).await?;
+            if count == 0 {
+                break;
+            }
+            backfilled += count;
+            info!(
+                backfilled,
+                "backfilled encryption context of secret versions"
+            );
+        }
+
+        loop {
+            let count = Self::backfill_vault_connections_batch(state, batch_size).await?;
+            if count == 0 {
+                break;
+            }
+            backfilled += count;
+            info!(
+                backfilled,
+                "backfilled encryption context of vault connections"
+            );
+        }
+
+        Ok(backfilled)
+    }
+
+    async fn backfill_secret_versions_batch(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<usize, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let versions =
+            SecretRepository::get_legacy_secret_versions_for_update(&mut tx, batch_size).await?;
+
+        for version in &versions {
+            let secret = SecretRepository::get_secret_by_id(&mut *tx, version.secret_id)
+                .await?
+                .ok_or(AppError::NotFoundError)?;
+            let context = EncryptionContext::secret_version(&secret.name, &version.version_tag);
+
+            let plaintext = Zeroizing::new(
+                crypto::decrypt(
+                    &state.db,
+                    &state.keyring,
+                    version.dek_id()?,
+                    &version.encrypted_secret,
+                    &context,
+                )
+                .await?,
+            );
+            let encrypted_payload =
+                crypto::encrypt(&mut tx, &state.keyring, &plaintext, &context).await?;
+
+            SecretRepository::update_secret_version_ciphertext(
+                &mut tx,
+                version.id,
+                &encrypted_payload.encrypted_blob,
+                encrypted_payload.dek_id,
+            )
+            .await?;
+            DekRepository::delete_dek(&mut tx, version.dek_id()?).await?;
+        }
+
+        tx.commit().await?;
+        Ok(versions.len())
+    }
+
+    async fn backfill_vault_connections_batch(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<usize, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let connections =
+            ConnectionRepository::get_legacy_vault_connections_for_update(&mut tx, batch_size)
+                .await?;
+
+        for connection in &connections {
+            let config =
+                ConnectionService::decrypt_connection_config(&state.db, &state.keyring, connection)
+                    .await?;
+            let encrypted_payload = crypto::encrypt(
+                &mut tx,
+                &state.keyring,
+                config.as_bytes(),
+                &EncryptionContext::vault_connection(&connection.public_id),
+            )
+            .await?;
+
+            ConnectionRepository::update_vault_connection_ciphertext(
+                &mut tx,
+                connection.id,
+                &encrypted_payload.encrypted_blob,
+                encrypted_payload.dek_id,
+            )
+            .await?;
+            DekRepository::delete_dek(&mut tx, connection.dek_id).await?;
+        }
+
+        tx.commit().await?;
+        Ok(connections.len())
+    }
+
+    /// Recompute the fingerprints of secret versions and vault connections from their
+    /// decrypted values
+    pub async fn backfill_fingerprints(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<usize, AppError> {
+        let mut backfilled = 0;
+
+        loop {
+            let count = Self::backfill_secret_version_fingerprints_batch(state, batch_size).await?;
+            if count == 0 {
+                break;
+            }
+            backfilled += count;
+            info!(backfilled, "backfilled fingerprints of secret versions");
+        }
+
+        loop {
+            let count =
+                Self::backfill_vault_connection_fingerprints_batch(state, batch_size).await?;
+            if count == 0 {
+                break;
+            }
+            backfilled += count;
+            info!(backfilled, "backfilled fingerprints of vault connections");
+        }
+
+        Ok(backfilled)
+    }
+
+    async fn backfill_secret_version_fingerprints_batch(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<usize, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let versions = SecretRepository::get_legacy_fingerprint_versions_for_update(
+            &mut tx,
+            crypto::FINGERPRINT_PREFIX,
+            batch_size,
+        )
+        .await?;
+
+        for version in &versions {
+            let secret = SecretRepository::get_secret_by_id(&mut *tx, version.secret_id)
+                .await?
+                .ok_or(AppError::NotFoundError)?;
+
+            let plaintext = Zeroizing::new(
+                crypto::decrypt(
+                    &state.db,
+                    &state.keyring,
+                    version.dek_id()?,
+                    &version.encrypted_secret,
+                    &EncryptionContext::secret_version(&secret.name, &version.version_tag),
+                )
+                .await?,
+            );
+
+            SecretRepository::update_secret_version_fingerprint(
+                &mut tx,
+                version.id,
+                &crypto::fingerprint(&state.keyring, &plaintext),
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(versions.len())
+    }
+
+    async fn backfill_vault_connection_fingerprints_batch(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<usize, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let connections = ConnectionRepository::get_legacy_fingerprint_connections_for_update(
+            &mut tx,
+            crypto::FINGERPRINT_PREFIX,
+            batch_size,
+        )
+        .await?;
+
+        for connection in &connections {
+            let config =
+                ConnectionService::decrypt_connection_config(&state.db, &state.keyring, connection)
+                    .await?;
+
+            ConnectionRepository::update_vault_connection_fingerprint(
+                &mut tx,
+                connection.id,
+                &crypto::fingerprint(&state.keyring, config.as_bytes()),
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(connections.len())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    errors::AppError,
+    models::{
+        AuthMethod, BEARER_TOKEN_NAME_PREFIX, CLIENT_CERTIFICATE_NAME_PREFIX, ClientIdentity,
+        ClientKeyResponse, CreateClientKeyRequest,
+    },
+    repositories::{client_keys::ClientKeyRepository, session_tokens::SessionTokenRepository},
+    signing::ClientVerifyingKey,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+pub struct ClientKeyService;
+
+impl ClientKeyService {
+    /// Register a client verifying key under a newly generated key ID
+    pub async fn register_client_key(
+        db: &PgPool,
+        request: CreateClientKeyRequest,
+    ) -> Result<ClientKeyResponse, AppError> {
+        if ClientIdentity::is_reserved_name(&request.name) {
+            return Err(AppError::InvalidInput(format!(
+                "Client key names cannot start with '{}' or '{}'",
+                BEARER_TOKEN_NAME_PREFIX, CLIENT_CERTIFICATE_NAME_PREFIX
+            )));
+        }
+        let public_key = request.public_key.trim();
+        let verifying_key = ClientVerifyingKey::parse(public_key).map_err(|_| {
+            AppError::InvalidInput("Invalid P-256, P-384 or Ed25519 public key".to_string())
+        })?;
+        let public_key = if public_key.starts_with("-----BEGIN") {
+            public_key.to_string()
+        } else {
+            public_key.to_ascii_lowercase()
+        };
+
+        if request
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            return Err(AppError::InvalidInput(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+
+        let key_id = uuid::Uuid::new_v4().to_string();
+        let key = ClientKeyRepository::create_client_key(
+            db,
+            &key_id,
+            &request.name,
+            verifying_key.algorithm(),
+            &public_key,
+            request.expires_at,
+        )
+        .await?;
+
+        info!(
+            key_id = %key.key_id,
+            name = %key.name,
+            algorithm = ?key.algorithm,
+            "client key registered"
+        );
+        Ok(key.into())
+    }
+
+    /// List all client keys, including expired and revoked ones
+    pub async fn list_client_keys(db: &PgPool) -> Result<Vec<ClientKeyResponse>, AppError> {
+        let keys = ClientKeyRepository::get_client_keys(db).await?;
+        Ok(keys.into_iter().map(ClientKeyResponse::from).collect())
+    }
+
+    /// Revoke a client key; requests signed with it are rejected from then on
+    pub async fn revoke_client_key(
+        db: &PgPool,
+        key_id: &str,
+    ) -> Result<ClientKeyResponse, AppError> {
+        let key = ClientKeyRepository::revoke_client_key(db, key_id)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+
+        let revoked_tokens =
+            SessionTokenRepository::revoke_session_tokens_by_key_id(db, &key.key_id).await?;
+
+        info!(
+            key_id = %key.key_id,
+            name = %key.name,
+            revoked_tokens,
+            "client key revoked"
+        );
+        Ok(key.into())
+    }
+
+    /// Look up the verifying key and identity of a usable client key
+    pub async fn resolve_client_key(
+        db: &PgPool,
+        key_id: &str,
+    ) -> Result<(ClientVerifyingKey, ClientIdentity), AppError> {
+        let key = ClientKeyRepository::get_client_key_by_key_id(db, key_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if !key.is_usable(Utc::now()) {
+            warn!(key_id = %key.key_id, "rejected expired or revoked client key");
+            return Err(AppError::Unauthorized);
+        }
+
+        let verifying_key = ClientVerifyingKey::parse(&key.public_key)?;
+        if verifying_key.algorithm() != key.algorithm {
+            return Err(AppError::CryptoError(format!(
+                "Client key {} does not match its registered algorithm",
+                key.key_id
+            )));
+        }
+        Ok((
+            verifying_key,
+            ClientIdentity {
+                key_id: Some(key.key_id),
+                name: key.name,
+                is_root: false,
+                auth_method: AuthMethod::Signature,
+                scope: None,
+                namespace: None,
+                audience: None,
+            },
+        ))
+    }
+}
@@ -1,6 +1,7 @@
-use crate::models::VaultConnectionConfig;
+use crate::keyring::Keyring;
+use crate::models::{VaultConnection, VaultConnectionConfig};
 use crate::{
-    crypto,
+    crypto::{self, EncryptionContext},
     errors::AppError,
     models::{
         CreateVaultConnectionRequest, CreateVaultConnectionResponse, UpdateVaultConnectionRequest,
@@ -9,7 +10,6 @@ use crate::{
     repositories::connections::ConnectionRepository,
     state::AppState,
 };
-use aws_sdk_kms::Client as KmsClient;
 use sqlx::PgPool;
 use std::sync::Arc;
 use zeroize::{Zeroize, Zeroizing};
@@ -52,13 +52,19 @@ impl ConnectionService {
 
         // Encrypt the configuration
         let config_bytes = payload.config.as_bytes();
-        let encrypted_payload = crypto::encrypt(&mut tx, &state.kms_client, &config_bytes).await?;
+        let encrypted_payload = crypto::encrypt(
+            &mut tx,
+            &state.keyring,
+            config_bytes,
+            &EncryptionContext::vault_connection(&payload.public_id),
+        )
+        .await?;
 
         // Insert into database
         let new_connection = ConnectionRepository::create_vault_connection(
             &mut tx,
             &payload,
-            &encrypted_payload.sha256sum,
+            &encrypted_payload.fingerprint,
             &encrypted_payload.encrypted_blob,
             encrypted_payload.dek_id,
         )
@@ -106,11 +112,16 @@ impl ConnectionService {
 
             Self::validate_vault_connection_config(state, integration_type, &config).await?;
             let config_bytes = config.as_bytes();
-            let encrypted_payload =
-                crypto::encrypt(&mut tx, &state.kms_client, &config_bytes).await?;
+            let encrypted_payload = crypto::encrypt(
+                &mut tx,
+                &state.keyring,
+                config_bytes,
+                &EncryptionContext::vault_connection(public_id),
+            )
+            .await?;
             config.zeroize();
             encrypted_config = Some(encrypted_payload.encrypted_blob);
-            sha256sum = Some(encrypted_payload.sha256sum);
+            sha256sum = Some(encrypted_payload.fingerprint);
             dek_id = Some(encrypted_payload.dek_id);
         }
 
@@ -142,20 +153,14 @@ impl ConnectionService {
     /// Get a vault connection by its public ID
     pub async fn get_vault_connection(
         db: &PgPool,
-        kms_client: &Arc<KmsClient>,
+        keyring: &Keyring,
         public_id: &str,
     ) -> Result<VaultConnectionResponse, AppError> {
         let connection = ConnectionRepository::get_vault_connection_by_public_id(db, public_id)
             .await?
             .ok_or(AppError::NotFoundError)?;
 
-        let config = Self::decrypt_connection_config(
-            db,
-            kms_client,
-            connection.dek_id,
-            &connection.encrypted_config,
-        )
-        .await?;
+        let config = Self::decrypt_connection_config(db, keyring, &connection).await?;
 
         let response = VaultConnectionResponse {
             id: connection.id,
@@ -174,20 +179,14 @@ impl ConnectionService {
     /// Get a vault connection by its ID
     pub async fn get_vault_connection_config_by_id(
         db: &PgPool,
-        kms_client: &Arc<KmsClient>,
+        keyring: &Keyring,
         id: i32,
     ) -> Result<VaultConnectionConfig, AppError> {
         let connection = ConnectionRepository::get_vault_connection_by_id(db, id)
             .await?
             .ok_or(AppError::NotFoundError)?;
 
-        let config = Self::decrypt_connection_config(
-            db,
-            kms_client,
-            connection.dek_id,
-            &connection.encrypted_config,
-        )
-        .await?;
+        let config = Self::decrypt_connection_config(db, keyring, &connection).await?;
 
         let config = VaultConnectionConfig {
             id: connection.id,
@@ -199,14 +198,19 @@ impl ConnectionService {
         Ok(config)
     }
 
-    async fn decrypt_connection_config(
+    pub async fn decrypt_connection_config(
         db: &PgPool,
-        kms_client: &Arc<KmsClient>,
-        dek_id: i32,
-        encrypted_config: &str,
+        keyring: &Keyring,
+        connection: &VaultConnection,
     ) -> Result<Zeroizing<String>, AppError> {
-        let decrypted_config_bytes =
-            crypto::decrypt(db, kms_client, dek_id, encrypted_config).await?;
+        let decrypted_config_bytes = crypto::decrypt(
+            db,
+            keyring,
+            connection.dek_id,
+            &connection.encrypted_config,
+            &EncryptionContext::vault_connection(&connection.public_id),
+        )
+        .await?;
 
         Ok(Zeroizing::new(
             String::from_utf8(decrypted_config_bytes).map_err(|e| {
@@ -0,0 +1,226 @@
+use crate::{
+    errors::AppError,
+    models::{CheckResult, CheckStatus, KekState, ReadinessResponse},
+    repositories::{connections::ConnectionRepository, kek::KekRepository},
+    services::{connections::ConnectionService, keks::KekService},
+    state::AppState,
+};
+use sqlx::migrate::Migrator;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// The migrations this build expects to have been applied.
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Upper bound on each readiness check, so a hung dependency fails the probe instead of
+/// stalling it.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Results of the readiness checks that call out to KMS or external vaults, which are
+/// reused for `ttl` so that frequent probes do not hammer them. Each entry stays locked
+/// while its check runs, so probes arriving together wait for one check instead of each
+/// running their own.
+pub struct ReadinessCache {
+    ttl: Duration,
+    keks: Mutex<Option<(Instant, CheckResult)>>,
+    providers: Mutex<Option<(Instant, BTreeMap<String, CheckResult>)>>,
+}
+
+impl ReadinessCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            keks: Mutex::new(None),
+            providers: Mutex::new(None),
+        }
+    }
+
+    /// Returns the result cached in `entry` if it is fresh, else runs `check` and caches
+    /// its result.
+    async fn get_or_check<T: Clone>(
+        &self,
+        entry: &Mutex<Option<(Instant, T)>>,
+        check: impl Future<Output = T>,
+    ) -> T {
+        let mut entry = entry.lock().await;
+        if let Some((checked_at, result)) = &*entry
+            && checked_at.elapsed() < self.ttl
+        {
+            return result.clone();
+        }
+
+        let result = check.await;
+        *entry = Some((Instant::now(), result.clone()));
+        result
+    }
+}
+
+pub struct HealthService;
+
+impl HealthService {
+    /// Check every dependency requests need, returning whether all of them are healthy
+    /// along with the result of each check
+    pub async fn readiness(state: &AppState) -> (bool, ReadinessResponse) {
+        let mut checks = BTreeMap::new();
+
+        let sealed = state.seal.as_ref().is_some_and(|seal| seal.is_sealed());
+        checks.insert(
+            "seal".to_string(),
+            if sealed {
+                CheckResult::error("vault is sealed")
+            } else {
+                CheckResult::ok()
+            },
+        );
+
+        let (database, migrations) = tokio::join!(
+            with_timeout(Self::check_database(state)),
+            with_timeout(Self::check_migrations(state)),
+        );
+        checks.insert("database".to_string(), database);
+        checks.insert("migrations".to_string(), migrations);
+
+        // KEKs cannot be reached while sealed, and need the database.
+        let keks = if sealed {
+            CheckResult::skipped("vault is sealed")
+        } else {
+            Self::check_keks(state).await
+        };
+        checks.insert("keks".to_string(), keks);
+
+        if state.readiness_check_providers && !sealed {
+            for (integration_type, result) in Self::check_providers(state).await {
+                checks.insert(format!("provider:{}", integration_type), result);
+            }
+        }
+
+        // Details can name KEKs, connections and upstream errors, so they are logged
+        // rather than returned to unauthenticated probes.
+        for (name, result) in &checks {
+            if let (CheckStatus::Error, Some(detail)) = (result.status, &result.detail) {
+                warn!(check = %name, detail = %detail, "readiness check failed");
+            }
+        }
+
+        let ready = checks.values().all(CheckResult::is_healthy);
+        (
+            ready,
+            ReadinessResponse {
+                status: if ready { "ready" } else { "not_ready" }.to_string(),
+                checks,
+            },
+        )
+    }
+
+    async fn check_database(state: &AppState) -> Result<Option<String>, AppError> {
+        sqlx::query("SELECT 1").execute(&state.db).await?;
+        Ok(None)
+    }
+
+    /// The schema is current if the latest migration of this build has been applied.
+    async fn check_migrations(state: &AppState) -> Result<Option<String>, AppError> {
+        let expected = MIGRATOR.iter().map(|migration| migration.version).max();
+        let applied: Option<i64> =
+            sqlx::query_scalar("SELECT max(version) FROM _sqlx_migrations WHERE success")
+                .fetch_one(&state.db)
+                .await?;
+
+        match (expected, applied) {
+            (Some(expected), Some(applied)) if applied >= expected => {
+                Ok(Some(format!("version {}", applied)))
+            }
+            (Some(expected), applied) => Err(AppError::InvalidInput(format!(
+                "expected version {}, applied {}",
+                expected,
+                applied.map_or("none".to_string(), |version| version.to_string())
+            ))),
+            (None, _) => Ok(None),
+        }
+    }
+
+    /// Healthy if at least one active KEK completes a wrap/unwrap round-trip.
+    async fn check_keks(state: &AppState) -> CheckResult {
+        let cache = &state.readiness_cache;
+        cache
+            .get_or_check(&cache.keks, Self::verify_active_keks(state))
+            .await
+    }
+
+    async fn verify_active_keks(state: &AppState) -> CheckResult {
+        with_timeout(async {
+            let keks = KekRepository::get_keks_by_state(&state.db, KekState::Active).await?;
+            let mut failures = Vec::new();
+            for kek in &keks {
+                if let Err(e) =
+                    KekService::verify_round_trip(&state.keyring, &kek.backend, &kek.kms_key).await
+                {
+                    failures.push(format!("KEK {}: {}", kek.id, e));
+                }
+            }
+
+            if failures.len() == keks.len() {
+                return Err(AppError::KmsError(if keks.is_empty() {
+                    "no active KEK".to_string()
+                } else {
+                    failures.join("; ")
+                }));
+            }
+            if !failures.is_empty() {
+                warn!(failures = %failures.join("; "), "active KEKs failed readiness check");
+            }
+            Ok(None)
+        })
+        .await
+    }
+
+    /// Validates the most recently updated connection of each registered provider, if
+    /// there is one, against its external vault.
+    async fn check_providers(state: &AppState) -> BTreeMap<String, CheckResult> {
+        let cache = &state.readiness_cache;
+        cache
+            .get_or_check(&cache.providers, Self::validate_providers(state))
+            .await
+    }
+
+    async fn validate_providers(state: &AppState) -> BTreeMap<String, CheckResult> {
+        let mut results = BTreeMap::new();
+        for (integration_type, factory) in state.provider_factories.iter() {
+            let result = with_timeout(async {
+                let Some(connection) =
+                    ConnectionRepository::get_latest_vault_connection(&state.db, integration_type)
+                        .await?
+                else {
+                    return Ok(None);
+                };
+
+                let config = ConnectionService::decrypt_connection_config(
+                    &state.db,
+                    &state.keyring,
+                    &connection,
+                )
+                .await?;
+                factory.validate(&config).await?;
+                Ok(None)
+            })
+            .await;
+            results.insert(integration_type.clone(), result);
+        }
+        results
+    }
+}
+
+async fn with_timeout(
+    check: impl Future<Output = Result<Option<String>, AppError>>,
+) -> CheckResult {
+    match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(detail)) => CheckResult {
+            detail,
+            ..CheckResult::ok()
+        },
+        Ok(Err(e)) => CheckResult::error(e.to_string()),
+        Err(_) => CheckResult::error(format!("timed out after {:?}", CHECK_TIMEOUT)),
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    crypto::EncryptionContext,
+    errors::AppError,
+    keyring::Keyring,
+    models::{CreateKekRequest, KekResponse, KekState, UpdateKekRequest},
+    repositories::kek::KekRepository,
+    state::AppState,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::info;
+
+pub struct KekService;
+
+const DEFAULT_KEK_WEIGHT: i32 = 1;
+
+impl KekService {
+    /// List every KEK with its DEK usage count
+    pub async fn list_keks(db: &PgPool) -> Result<Vec<KekResponse>, AppError> {
+        let usages = KekRepository::get_kek_usages(db).await?;
+        Ok(usages.into_iter().map(KekResponse::from).collect())
+    }
+
+    /// Get a single KEK with its DEK usage count
+    pub async fn get_kek(db: &PgPool, id: i32) -> Result<KekResponse, AppError> {
+        let usage = KekRepository::get_kek_usage_by_id(db, id)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        Ok(usage.into())
+    }
+
+    /// Register a new active KEK once a round-trip through its backend succeeds
+    pub async fn register_kek(
+        state: &Arc<AppState>,
+        request: CreateKekRequest,
+    ) -> Result<KekResponse, AppError> {
+        Self::verify_round_trip(&state.keyring, &request.backend, &request.kms_key).await?;
+
+        let mut tx = state.db.begin().await?;
+        let kek = KekRepository::create_kek(
+            &mut tx,
+            &request.backend,
+            &request.kms_key,
+            request.weight.unwrap_or(DEFAULT_KEK_WEIGHT),
+            Utc::now(),
+        )
+        .await?;
+        tx.commit().await?;
+
+        info!(kek_id = kek.id, backend = %kek.backend, "KEK registered");
+        Self::get_kek(&state.db, kek.id).await
+    }
+
+    /// Change the state and/or weight of a KEK
+    pub async fn update_kek(
+        state: &Arc<AppState>,
+        id: i32,
+        request: UpdateKekRequest,
+    ) -> Result<KekResponse, AppError> {
+        let kek = KekRepository::get_kek_usage_by_id(&state.db, id)
+            .await?
+            .ok_or(AppError::NotFoundError)?
+            .kek;
+
+        // The round-trip goes through the KMS, so it runs before the row is locked.
+        let verified_at =
+            if request.state == Some(KekState::Active) && kek.state != KekState::Active {
+                Self::verify_round_trip(&state.keyring, &kek.backend, &kek.kms_key).await?;
+                Some(Utc::now())
+            } else {
+                None
+            };
+
+        let mut tx = state.db.begin().await?;
+
+        let kek = KekRepository::get_kek_by_id_for_update(&mut tx, id)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+
+        if let Some(new_state) = request.state
+            && new_state != kek.state
+        {
+            match new_state {
+                KekState::Active => {
+                    // The KEK was already active when checked, but no longer is.
+                    let verified_at = verified_at.ok_or(AppError::Conflict)?;
+                    KekRepository::update_kek_verified_at(&mut *tx, kek.id, verified_at).await?;
+                }
+                KekState::Retired => {
+                    let usage = KekRepository::get_kek_usage_by_id(&mut *tx, kek.id)
+                        .await?
+                        .ok_or(AppError::NotFoundError)?;
+                    if usage.dek_count > 0 {
+                        return Err(AppError::InvalidInput(format!(
+                            "KEK still wraps {} DEKs; set it to decrypt_only to re-wrap them first",
+                            usage.dek_count
+                        )));
+                    }
+                }
+                KekState::DecryptOnly => {}
+            }
+            KekRepository::update_kek_state(&mut *tx, kek.id, new_state).await?;
+        }
+
+        if let Some(weight) = request.weight {
+            KekRepository::update_kek_weight(&mut tx, kek.id, weight).await?;
+        }
+
+        if KekRepository::count_selectable_keks(&mut tx).await? == 0 {
+            return Err(AppError::InvalidInput(
+                "At least one active KEK with a non-zero weight is required".to_string(),
+            ));
+        }
+
+        tx.commit().await?;
+
+        info!(kek_id = kek.id, state = ?request.state, weight = ?request.weight, "KEK updated");
+        Self::get_kek(&state.db, kek.id).await
+    }
+
+    /// Delete a KEK once it is retired and no DEKs reference it
+    pub async fn delete_kek(db: &PgPool, id: i32) -> Result<(), AppError> {
+        let mut tx = db.begin().await?;
+
+        let kek = KekRepository::get_kek_by_id_for_update(&mut tx, id)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        if kek.state != KekState::Retired {
+            return Err(AppError::InvalidInput(
+                "Only retired KEKs can be deleted".to_string(),
+            ));
+        }
+        // Retiring checked this, but the foreign key would also refuse the delete.
+        let usage = KekRepository::get_kek_usage_by_id(&mut *tx, kek.id)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        if usage.dek_count > 0 {
+            return Err(AppError::Conflict);
+        }
+
+        KekRepository::delete_kek(&mut tx, kek.id).await?;
+        tx.commit().await?;
+
+        info!(kek_id = kek.id, backend = %kek.backend, "KEK deleted");
+        Ok(())
+    }
+
+    /// Re-run the round-trip check for an existing KEK
+    pub async fn verify_kek(state: &Arc<AppState>, id: i32) -> Result<KekResponse, AppError> {
+        let kek = KekRepository::get_kek_usage_by_id(&state.db, id)
+            .await?
+            .ok_or(AppError::NotFoundError)?
+            .kek;
+
+        Self::verify_round_trip(&state.keyring, &kek.backend, &kek.kms_key).await?;
+        KekRepository::update_kek_verified_at(&state.db, kek.id, Utc::now()).await?;
+
+        Self::get_kek(&state.db, kek.id).await
+    }
+
+    /// Register the configured bootstrap KEK unless it already exists
+    pub async fn bootstrap_kek(
+        state: &Arc<AppState>,
+        backend: &str,
+        kms_key: &str,
+    ) -> Result<(), AppError> {
+        if let Some(kek) = KekRepository::find_kek(&state.db, backend, kms_key).await? {
+            info!(kek_id = kek.id, "bootstrap KEK already registered");
+            return Ok(());
+        }
+
+        Self::register_kek(
+            state,
+            CreateKekRequest {
+                backend: backend.to_string(),
+                kms_key: kms_key.to_string(),
+                weight: None,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Generate a data key under the KEK and check that it unwraps to the same key
+    pub async fn verify_round_trip(
+        keyring: &Keyring,
+        backend: &str,
+        kms_key: &str,
+    ) -> Result<(), AppError> {
+        let wrapper = keyring.wrapper(backend).map_err(|_| {
+            AppError::InvalidInput(format!(
+                "Key wrapping backend '{}' is not configured",
+                backend
+            ))
+        })?;
+        let context = EncryptionContext::kek_verification();
+        let verification_failed =
+            |e: AppError| AppError::InvalidInput(format!("KEK verification failed: {}", e));
+
+        let data_key = wrapper
+            .generate_data_key(kms_key, &context)
+            .await
+            .map_err(verification_failed)?;
+        let unwrapped = wrapper
+            .unwrap_data_key(kms_key, &data_key.wrapped, &context)
+            .await
+            .map_err(verification_failed)?;
+
+        if *unwrapped != *data_key.plaintext {
+            return Err(AppError::InvalidInput(
+                "KEK verification failed: unwrapped key does not match".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
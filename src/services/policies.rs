@@ -0,0 +1,227 @@
+use crate::{
+    errors::AppError,
+    models::{Capability, ClientIdentity, CreatePolicyRequest, Policy, PolicyResponse},
+    repositories::policies::PolicyRepository,
+};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+pub struct PolicyService;
+
+/// The resource system-wide administration is authorized against; only policies whose
+/// pattern matches everything (`*`) grant it.
+const SYSTEM_RESOURCE: &str = "*";
+
+/// Prefix of the resource vault connections are authorized against. Secret names cannot
+/// contain `:`, so patterns written for secrets never match a connection.
+const CONNECTION_RESOURCE_PREFIX: &str = "connection:";
+
+/// The resource a vault connection is authorized against, `connection:<public_id>`.
+pub fn connection_resource(public_id: &str) -> String {
+    format!("{}{}", CONNECTION_RESOURCE_PREFIX, public_id)
+}
+
+impl PolicyService {
+    /// Check that a client holds `capability` on `resource`, a secret name or a
+    /// [`connection_resource`]
+    pub async fn authorize(
+        db: &PgPool,
+        identity: &ClientIdentity,
+        capability: Capability,
+        resource: &str,
+    ) -> Result<(), AppError> {
+        Self::authorize_all(db, identity, capability, &[resource]).await
+    }
+
+    /// Check that a client holds `capability` on every one of `resources`, loading its
+    /// policies once
+    pub async fn authorize_all(
+        db: &PgPool,
+        identity: &ClientIdentity,
+        capability: Capability,
+        resources: &[&str],
+    ) -> Result<(), AppError> {
+        if let Some(scope) = &identity.scope
+            && !scope.contains(&capability)
+            && !scope.contains(&Capability::Admin)
+        {
+            warn!(client = %identity.name, ?capability, ?resources, "capability outside token scope");
+            return Err(AppError::Forbidden);
+        }
+
+        if identity.is_root {
+            return Ok(());
+        }
+
+        let policies = PolicyRepository::get_policies_by_client_name(db, &identity.name).await?;
+        for resource in resources {
+            if !grants(&policies, capability, resource) {
+                warn!(client = %identity.name, ?capability, resource, "request forbidden");
+                return Err(AppError::Forbidden);
+            }
+        }
+        Ok(())
+    }
+
+    /// The patterns of the policies granting a client `capability`, for listings that must
+    /// only return what the client can access. `None` if the client is not restricted
+    /// by policies.
+    pub async fn granting_patterns(
+        db: &PgPool,
+        identity: &ClientIdentity,
+        capability: Capability,
+    ) -> Result<Option<Vec<String>>, AppError> {
+        if let Some(scope) = &identity.scope
+            && !scope.contains(&capability)
+            && !scope.contains(&Capability::Admin)
+        {
+            warn!(client = %identity.name, ?capability, "capability outside token scope");
+            return Err(AppError::Forbidden);
+        }
+
+        if identity.is_root {
+            return Ok(None);
+        }
+
+        let policies = PolicyRepository::get_policies_by_client_name(db, &identity.name).await?;
+        Ok(Some(
+            policies
+                .into_iter()
+                .filter(|policy| {
+                    policy.capabilities.contains(&capability)
+                        || policy.capabilities.contains(&Capability::Admin)
+                })
+                .map(|policy| policy.pattern)
+                .collect(),
+        ))
+    }
+
+    /// Check that a client may administer the vault itself: KEKs, client keys, policies
+    /// and the seal
+    pub async fn require_admin(db: &PgPool, identity: &ClientIdentity) -> Result<(), AppError> {
+        Self::authorize(db, identity, Capability::Admin, SYSTEM_RESOURCE).await
+    }
+
+    /// List all policies
+    pub async fn list_policies(db: &PgPool) -> Result<Vec<PolicyResponse>, AppError> {
+        let policies = PolicyRepository::get_policies(db).await?;
+        Ok(policies.into_iter().map(PolicyResponse::from).collect())
+    }
+
+    /// Create a policy granting capabilities to a client
+    pub async fn create_policy(
+        db: &PgPool,
+        request: CreatePolicyRequest,
+    ) -> Result<PolicyResponse, AppError> {
+        let policy = PolicyRepository::create_policy(
+            db,
+            &request.client_name,
+            &request.capabilities,
+            &request.pattern,
+        )
+        .await?;
+
+        info!(
+            policy_id = policy.id,
+            client = %policy.client_name,
+            pattern = %policy.pattern,
+            "policy created"
+        );
+        Ok(policy.into())
+    }
+
+    /// Delete a policy
+    pub async fn delete_policy(db: &PgPool, id: i32) -> Result<bool, AppError> {
+        let rows_affected = PolicyRepository::delete_policy(db, id).await?;
+        Ok(rows_affected > 0)
+    }
+}
+
+/// Whether any of a client's policies grants `capability`, or `admin`, on `resource`.
+fn grants(policies: &[Policy], capability: Capability, resource: &str) -> bool {
+    policies.iter().any(|policy| {
+        (policy.capabilities.contains(&capability)
+            || policy.capabilities.contains(&Capability::Admin))
+            && pattern_matches(&policy.pattern, resource)
+    })
+}
+
+/// Glob match where `*` matches any sequence of characters, including `/`.
+fn pattern_matches(pattern: &str, resource: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let resource = resource.as_bytes();
+    let (mut p, mut r) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while r < resource.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, r));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == resource[r] {
+            p += 1;
+            r += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` absorb one more character and retry.
+            p = star + 1;
+            r = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{connection_resource, pattern_matches};
+
+    #[test]
+    fn star_matches_everything() {
+        assert!(pattern_matches("*", ""));
+        assert!(pattern_matches("*", "payments/stripe/api-key"));
+        assert!(pattern_matches("**", "a"));
+    }
+
+    #[test]
+    fn literal_patterns_match_exactly() {
+        assert!(pattern_matches("payments/stripe", "payments/stripe"));
+        assert!(!pattern_matches(
+            "payments/stripe",
+            "payments/stripe-legacy"
+        ));
+        assert!(!pattern_matches("payments/stripe", "payments/strip"));
+        assert!(pattern_matches("", ""));
+        assert!(!pattern_matches("", "a"));
+    }
+
+    #[test]
+    fn trailing_star_matches_a_folder_and_everything_under_it() {
+        assert!(pattern_matches("payments/*", "payments/"));
+        assert!(pattern_matches("payments/*", "payments/stripe/api-key"));
+        assert!(!pattern_matches("payments/*", "payments"));
+        assert!(!pattern_matches("payments/*", "paymentsx/a"));
+    }
+
+    #[test]
+    fn inner_stars_backtrack() {
+        assert!(pattern_matches("*-key", "payments/stripe/api-key"));
+        assert!(!pattern_matches("*-key", "payments/stripe/api-key2"));
+        assert!(pattern_matches("a*b*c", "abc"));
+        assert!(pattern_matches("a*b*c", "aXbYbZc"));
+        assert!(!pattern_matches("a*b*c", "aXcYb"));
+        assert!(pattern_matches("*x", "xx"));
+        assert!(pattern_matches("*/*/key", "a/b/key"));
+        assert!(pattern_matches("*/*/key", "a/b/c/key"));
+    }
+
+    #[test]
+    fn connections_have_their_own_namespace() {
+        let connection = connection_resource("payments-prod");
+        assert_eq!(connection, "connection:payments-prod");
+        assert!(!pattern_matches("pay*", &connection));
+        assert!(pattern_matches("connection:pay*", &connection));
+        assert!(pattern_matches("*", &connection));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    errors::AppError,
+    repositories::{
+        dek::DekRepository, secrets::SecretRepository, shred_records::ShredRecordRepository,
+    },
+    services::secrets::SecretService,
+    state::AppState,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+pub struct SecretPurgeService;
+
+impl SecretPurgeService {
+    /// Spawn a background task that periodically hard-deletes secrets and versions whose
+    /// recovery window has passed
+    pub fn spawn_purger(
+        state: Arc<AppState>,
+        interval: Duration,
+        batch_size: i64,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = Self::purge_deleted(&state, batch_size).await {
+                    error!("Secret purge failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Hard-delete every secret and version soft-deleted before the recovery window,
+    /// shredding their DEKs
+    pub async fn purge_deleted(state: &Arc<AppState>, batch_size: i64) -> Result<(), AppError> {
+        let deleted_before = Utc::now() - state.secret_recovery_window;
+        let mut purged_secrets = 0;
+        let mut purged_versions = 0;
+
+        loop {
+            let mut tx = state.db.begin().await?;
+            let secrets = SecretRepository::get_purgeable_secrets_for_update(
+                &mut tx,
+                deleted_before,
+                batch_size,
+            )
+            .await?;
+            if secrets.is_empty() {
+                break;
+            }
+
+            let mut dek_ids = Vec::new();
+            for secret in secrets {
+                let (_, secret_dek_ids) =
+                    SecretService::destroy_locked_secret(&mut tx, secret, Utc::now()).await?;
+                dek_ids.extend(secret_dek_ids);
+                purged_secrets += 1;
+            }
+            tx.commit().await?;
+
+            for dek_id in dek_ids {
+                state.keyring.dek_cache().evict(dek_id);
+            }
+        }
+
+        loop {
+            let mut tx = state.db.begin().await?;
+            let versions = SecretRepository::get_purgeable_secret_versions_for_update(
+                &mut tx,
+                deleted_before,
+                batch_size,
+            )
+            .await?;
+            if versions.is_empty() {
+                break;
+            }
+
+            let mut dek_ids = Vec::new();
+            for version in versions {
+                let secret = SecretRepository::get_secret_by_id(&mut *tx, version.secret_id)
+                    .await?
+                    .ok_or(AppError::NotFoundError)?;
+
+                // The version references its DEK, so it is deleted first.
+                SecretRepository::delete_secret_version(&mut tx, version.id).await?;
+                if let Some(dek_id) = version.dek_id {
+                    let dek = DekRepository::delete_dek(&mut tx, dek_id).await?;
+                    ShredRecordRepository::create_shred_record(
+                        &mut tx,
+                        &secret.name,
+                        &version.version_tag,
+                        &dek,
+                    )
+                    .await?;
+                    dek_ids.push(dek_id);
+                }
+                purged_versions += 1;
+            }
+            tx.commit().await?;
+
+            for dek_id in dek_ids {
+                state.keyring.dek_cache().evict(dek_id);
+            }
+        }
+
+        if purged_secrets > 0 || purged_versions > 0 {
+            info!(
+                secrets = purged_secrets,
+                versions = purged_versions,
+                "deleted secrets purged"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{errors::AppError, repositories::nonces::NonceRepository, state::AppState};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+pub struct ReplayService;
+
+impl ReplayService {
+    /// Record the nonce of an authenticated request, rejecting it if the same client
+    /// already used it within the recv window
+    pub async fn check_nonce(
+        state: &AppState,
+        client_name: &str,
+        nonce: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let now = Utc::now();
+        if !state.nonce_cache.insert(
+            client_name,
+            nonce,
+            expires_at.timestamp_millis(),
+            now.timestamp_millis(),
+        ) {
+            return Err(replayed());
+        }
+
+        if state.replay_cache_shared
+            && !NonceRepository::insert_nonce(&state.db, client_name, nonce, expires_at).await?
+        {
+            return Err(replayed());
+        }
+
+        Ok(())
+    }
+
+    /// Periodically drop expired nonces from the in-memory cache and, if enabled, the
+    /// shared store
+    pub fn spawn_pruner(state: Arc<AppState>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                state.nonce_cache.prune(Utc::now().timestamp_millis());
+
+                if state.replay_cache_shared {
+                    match NonceRepository::delete_expired_nonces(&state.db).await {
+                        Ok(0) => {}
+                        Ok(pruned) => info!(pruned, "expired request nonces pruned"),
+                        Err(e) => error!("Failed to prune request nonces: {}", e),
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn replayed() -> AppError {
+    AppError::InvalidInput("Nonce has already been used".to_string())
+}
@@ -0,0 +1,121 @@
+use crate::{
+    errors::AppError,
+    models::{KekState, KeyEncryptionKey},
+    repositories::{dek::DekRepository, kek::KekRepository},
+    state::AppState,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+pub struct KekRotationService;
+
+impl KekRotationService {
+    /// Spawn a background task that periodically re-wraps the DEKs of decrypt-only KEKs
+    pub fn spawn_worker(
+        state: Arc<AppState>,
+        interval: Duration,
+        batch_size: i64,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = Self::rotate_decrypt_only_keks(&state, batch_size).await {
+                    error!("KEK rotation failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Re-wrap every DEK of every decrypt-only KEK under an active KEK
+    pub async fn rotate_decrypt_only_keks(
+        state: &Arc<AppState>,
+        batch_size: i64,
+    ) -> Result<(), AppError> {
+        let keks = KekRepository::get_keks_by_state(&state.db, KekState::DecryptOnly).await?;
+
+        for kek in keks {
+            Self::rewrap_kek(state, &kek, batch_size).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn rewrap_kek(
+        state: &Arc<AppState>,
+        kek: &KeyEncryptionKey,
+        batch_size: i64,
+    ) -> Result<(), AppError> {
+        let total = DekRepository::count_deks_by_kek(&state.db, kek.id).await?;
+        let source_wrapper = state.keyring.wrapper(&kek.backend)?;
+        let mut rewrapped = 0;
+
+        info!(
+            kek_id = kek.id,
+            total, "re-wrapping DEKs of decrypt-only KEK"
+        );
+
+        loop {
+            let mut tx = state.db.begin().await?;
+
+            let deks =
+                DekRepository::get_deks_by_kek_for_update(&mut tx, kek.id, batch_size).await?;
+            if deks.is_empty() {
+                break;
+            }
+
+            let target = KekRepository::select_active_kek(&mut tx).await?;
+            let target_wrapper = state.keyring.wrapper(&target.backend)?;
+
+            for dek in &deks {
+                let context = dek
+                    .encryption_context
+                    .as_ref()
+                    .map(|context| context.0.clone())
+                    .unwrap_or_default();
+                let plaintext_dek = source_wrapper
+                    .unwrap_data_key(&kek.kms_key, &dek.encrypted_key, &context)
+                    .await?;
+                let rewrapped_dek = target_wrapper
+                    .wrap_data_key(&target.kms_key, &plaintext_dek, &context)
+                    .await?;
+
+                DekRepository::update_dek_wrapping(&mut tx, dek.id, target.id, &rewrapped_dek)
+                    .await?;
+            }
+
+            tx.commit().await?;
+
+            rewrapped += deks.len();
+            info!(
+                kek_id = kek.id,
+                target_kek_id = target.id,
+                rewrapped,
+                total,
+                "re-wrapped DEK batch"
+            );
+        }
+
+        // Drop unwrapped keys that were cached while still wrapped under the rotated KEK.
+        if rewrapped > 0 {
+            state.keyring.dek_cache().flush();
+        }
+
+        // Rows locked by another worker were skipped; retire only once nothing references the KEK.
+        let remaining = DekRepository::count_deks_by_kek(&state.db, kek.id).await?;
+        if remaining > 0 {
+            warn!(
+                kek_id = kek.id,
+                remaining, "DEKs still wrapped under decrypt-only KEK"
+            );
+            return Ok(());
+        }
+
+        KekRepository::update_kek_state(&state.db, kek.id, KekState::Retired).await?;
+        info!(kek_id = kek.id, rewrapped, "KEK retired");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    errors::AppError,
+    keyring::{local::MASTER_KEY_SIZE, shamir::Seal},
+    models::{InitSealRequest, InitSealResponse, SealStatusResponse, UnsealRequest},
+    repositories::seal::SealRepository,
+    shamir::{self, Share},
+    state::AppState,
+};
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+use tracing::{info, warn};
+use zeroize::Zeroizing;
+
+pub struct SealService;
+
+const KEY_CHECK_LABEL: &[u8] = b"lockset-vault seal key check";
+
+impl SealService {
+    /// Report whether the vault is initialized and sealed, and the unseal progress
+    pub async fn status(state: &Arc<AppState>) -> Result<SealStatusResponse, AppError> {
+        let seal = Self::seal(state)?;
+        let config = SealRepository::get_seal_config(&state.db).await?;
+
+        Ok(SealStatusResponse {
+            initialized: config.is_some(),
+            sealed: seal.is_sealed(),
+            threshold: config.as_ref().map(|config| config.threshold),
+            shares: config.as_ref().map(|config| config.share_count),
+            progress: seal.progress(),
+        })
+    }
+
+    /// Generate a master key and split it into shares. The vault stays sealed.
+    pub async fn init(
+        state: &Arc<AppState>,
+        request: InitSealRequest,
+    ) -> Result<InitSealResponse, AppError> {
+        Self::seal(state)?;
+        if request.threshold > request.shares {
+            return Err(AppError::InvalidInput(
+                "Threshold cannot exceed the number of shares".to_string(),
+            ));
+        }
+
+        let mut tx = state.db.begin().await?;
+        if SealRepository::get_seal_config(&mut *tx).await?.is_some() {
+            return Err(AppError::Conflict);
+        }
+
+        let mut master_key = Zeroizing::new(vec![0u8; MASTER_KEY_SIZE]);
+        OsRng.fill_bytes(&mut master_key);
+        let shares = shamir::split(&master_key, request.threshold as u8, request.shares as u8)?;
+
+        SealRepository::create_seal_config(
+            &mut tx,
+            request.threshold,
+            request.shares,
+            &key_check(&master_key),
+        )
+        .await?;
+        tx.commit().await?;
+
+        info!(
+            threshold = request.threshold,
+            shares = request.shares,
+            "vault initialized"
+        );
+
+        Ok(InitSealResponse {
+            shares: shares.iter().map(Share::to_hex).collect(),
+            threshold: request.threshold,
+        })
+    }
+
+    /// Submit an unseal share, unsealing once the threshold is reached
+    pub async fn unseal(
+        state: &Arc<AppState>,
+        request: UnsealRequest,
+    ) -> Result<SealStatusResponse, AppError> {
+        let seal = Self::seal(state)?;
+        let config = SealRepository::get_seal_config(&state.db)
+            .await?
+            .ok_or_else(|| AppError::InvalidInput("Vault is not initialized".to_string()))?;
+
+        if seal.is_sealed() {
+            let progress = seal.add_share(Share::from_hex(&request.share)?);
+
+            if progress >= config.threshold as usize {
+                let shares = seal.take_pending_shares();
+                let master_key = shamir::combine(&shares)?;
+
+                if !key_check_matches(&master_key, &config.key_check) {
+                    warn!("unseal shares did not reconstruct the master key");
+                    return Err(AppError::InvalidInput(
+                        "Unseal failed: the submitted shares do not reconstruct the master key"
+                            .to_string(),
+                    ));
+                }
+
+                seal.unseal(master_key)?;
+                info!("vault unsealed");
+            }
+        }
+
+        Self::status(state).await
+    }
+
+    /// Drop the master key and every cached DEK
+    pub async fn seal_vault(state: &Arc<AppState>) -> Result<SealStatusResponse, AppError> {
+        Self::seal(state)?.seal();
+        state.keyring.dek_cache().flush();
+        info!("vault sealed");
+
+        Self::status(state).await
+    }
+
+    fn seal(state: &Arc<AppState>) -> Result<&Seal, AppError> {
+        state
+            .seal
+            .as_deref()
+            .ok_or_else(|| AppError::InvalidInput("Sealed mode is not enabled".to_string()))
+    }
+}
+
+fn key_check(master_key: &[u8]) -> Vec<u8> {
+    key_check_mac(master_key).finalize().into_bytes().to_vec()
+}
+
+fn key_check_matches(master_key: &[u8], expected: &[u8]) -> bool {
+    key_check_mac(master_key).verify_slice(expected).is_ok()
+}
+
+fn key_check_mac(master_key: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(master_key).expect("HMAC accepts keys of any length");
+    mac.update(KEY_CHECK_LABEL);
+    mac
+}
@@ -1,17 +1,24 @@
+use crate::keyring::Keyring;
 use crate::regex::get_ending_number_regex;
 use crate::services::connections::ConnectionService;
+use crate::services::policies::PolicyService;
 use crate::{
-    crypto,
+    crypto::{self, EncryptionContext},
     errors::AppError,
     models::{
-        CreateSecretRequest, CreateSecretResponse, CreateSecretVersionRequest,
-        CreateSecretVersionResponse, Secret, SecretResponse,
+        Capability, ClientIdentity, CreateSecretRequest, CreateSecretResponse,
+        CreateSecretVersionRequest, CreateSecretVersionResponse, DeleteSecretResponse,
+        DestroyFolderResponse, DestroySecretResponse, FolderResponse, ListSecretVersionsQuery,
+        ListSecretVersionsResponse, ListSecretsQuery, ListSecretsResponse,
+        PromoteSecretVersionResponse, RestoreSecretResponse, Secret, SecretMetadataResponse,
+        SecretResponse, SecretVersion, SecretVersionMetadataResponse,
+    },
+    repositories::{
+        dek::DekRepository, secrets::SecretRepository, shred_records::ShredRecordRepository,
     },
-    repositories::secrets::SecretRepository,
     state::AppState,
 };
-use aws_sdk_kms::Client as KmsClient;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::{PgPool, Postgres, Transaction};
 use std::sync::Arc;
 use tracing::info;
@@ -20,6 +27,7 @@ use zeroize::Zeroizing;
 pub struct SecretService;
 
 const DEFAULT_TTL_SECONDS: i32 = 3600; // 1 hour
+const DEFAULT_LIST_LIMIT: i64 = 100;
 
 impl SecretService {
     /// Create a new secret with its first version
@@ -32,14 +40,19 @@ impl SecretService {
         let (secret_value, vault_connection_id) = if let Some(public_id) = &request.vault_connection
         {
             let (value, connection_id) =
-                Self::get_secret_value_from_provider(state, &request.name, &public_id).await?;
+                Self::get_secret_value_from_provider(state, &request.name, public_id).await?;
             (value, Some(connection_id))
         } else {
             (request.value.unwrap_or_default(), None)
         };
 
-        let encrypted_payload =
-            crypto::encrypt(&mut tx, &state.kms_client, secret_value.as_bytes()).await?;
+        let encrypted_payload = crypto::encrypt(
+            &mut tx,
+            &state.keyring,
+            secret_value.as_bytes(),
+            &EncryptionContext::secret_version(&request.name, &request.version_tag),
+        )
+        .await?;
 
         let secret = SecretRepository::create_secret(
             &mut tx,
@@ -53,7 +66,7 @@ impl SecretService {
             &mut tx,
             secret.id,
             &request.version_tag,
-            &encrypted_payload.sha256sum,
+            &encrypted_payload.fingerprint,
             &encrypted_payload.encrypted_blob,
             encrypted_payload.dek_id,
         )
@@ -78,7 +91,7 @@ impl SecretService {
 
         if let Some(vc_id) = secret.vault_connection_id {
             // If it's a proxied secret, and it's expired, refresh it
-            let should_refresh = secret.expire_at.map_or(true, |ea| Utc::now() > ea);
+            let should_refresh = secret.expire_at.is_none_or(|ea| Utc::now() > ea);
 
             if should_refresh {
                 return Self::refresh_proxied_secret(state, secret, vc_id).await;
@@ -90,15 +103,11 @@ impl SecretService {
         let version =
             SecretRepository::get_secret_version_by_tag(&state.db, secret.id, &current_version_tag)
                 .await?
+                .filter(|version| !version.deleted)
                 .ok_or(AppError::NotFoundError)?;
 
-        let decrypted_value = Self::decrypt_secret_value(
-            &state.db,
-            &state.kms_client,
-            &version.encrypted_secret,
-            version.dek_id,
-        )
-        .await?;
+        let decrypted_value =
+            Self::decrypt_secret_value(&state.db, &state.keyring, &secret.name, &version).await?;
 
         Ok(SecretResponse {
             name: secret.name,
@@ -125,42 +134,51 @@ impl SecretService {
         }
 
         // Encrypt the secret value
-        let encrypted_payload =
-            crypto::encrypt(&mut tx, &state.kms_client, request.value.as_bytes()).await?;
+        let encrypted_payload = crypto::encrypt(
+            &mut tx,
+            &state.keyring,
+            request.value.as_bytes(),
+            &EncryptionContext::secret_version(&secret.name, &request.version_tag),
+        )
+        .await?;
 
         // Insert the new version
         let new_version = SecretRepository::create_secret_version(
             &mut tx,
             secret.id,
             &request.version_tag,
-            &encrypted_payload.sha256sum,
+            &encrypted_payload.fingerprint,
             &encrypted_payload.encrypted_blob,
             encrypted_payload.dek_id,
         )
         .await?;
 
         // Update the parent secret record
-        let previous_version = secret.current_version.take();
-        SecretRepository::update_secret_versions(
-            &mut tx,
-            secret.id,
-            &request.version_tag,
-            previous_version,
-        )
-        .await?;
+        let promoted = request.promote.unwrap_or(true);
+        if promoted {
+            let previous_version = secret.current_version.take();
+            SecretRepository::update_secret_versions(
+                &mut tx,
+                secret.id,
+                &request.version_tag,
+                previous_version,
+            )
+            .await?;
+        }
 
         tx.commit().await?;
         Ok(CreateSecretVersionResponse {
             name: secret.name,
             version_tag: new_version.version_tag,
             created_at: new_version.created_at,
+            promoted,
         })
     }
 
     /// Get a specific version of a secret
     pub async fn get_secret_version(
         db: &PgPool,
-        kms_client: &Arc<KmsClient>,
+        keyring: &Keyring,
         name: &str,
         tag: &str,
     ) -> Result<SecretResponse, AppError> {
@@ -170,11 +188,11 @@ impl SecretService {
 
         let version = SecretRepository::get_secret_version_by_tag(db, secret.id, tag)
             .await?
+            .filter(|version| !version.deleted)
             .ok_or(AppError::NotFoundError)?;
 
         let decrypted_value =
-            Self::decrypt_secret_value(db, kms_client, &version.encrypted_secret, version.dek_id)
-                .await?;
+            Self::decrypt_secret_value(db, keyring, &secret.name, &version).await?;
 
         Ok(SecretResponse {
             name: secret.name,
@@ -183,17 +201,509 @@ impl SecretService {
         })
     }
 
+    /// Destroy a secret and all of its versions by deleting their DEKs
+    pub async fn destroy_secret(
+        state: &Arc<AppState>,
+        name: &str,
+    ) -> Result<DestroySecretResponse, AppError> {
+        let destroyed_at = Utc::now();
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_any_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        let (response, dek_ids) =
+            Self::destroy_locked_secret(&mut tx, secret, destroyed_at).await?;
+
+        tx.commit().await?;
+
+        for dek_id in dek_ids {
+            state.keyring.dek_cache().evict(dek_id);
+        }
+        info!(secret = %response.name, versions = response.destroyed_versions.len(), "secret destroyed");
+
+        Ok(response)
+    }
+
+    /// List the metadata of secrets matching `query`, a page at a time
+    pub async fn list_secrets(
+        db: &PgPool,
+        identity: &ClientIdentity,
+        query: ListSecretsQuery,
+    ) -> Result<ListSecretsResponse, AppError> {
+        let after = query.cursor.as_deref().map(decode_cursor).transpose()?;
+        let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+
+        let readable = PolicyService::granting_patterns(db, identity, Capability::Read).await?;
+
+        // One extra row tells whether there is another page.
+        let mut secrets =
+            SecretRepository::list_secrets(db, &query, readable.as_deref(), after, limit + 1)
+                .await?;
+        let next_cursor = if secrets.len() as i64 > limit {
+            secrets.truncate(limit as usize);
+            secrets
+                .last()
+                .map(|last| encode_cursor(last.created_at, last.id))
+        } else {
+            None
+        };
+        Ok(ListSecretsResponse {
+            secrets: secrets
+                .into_iter()
+                .map(SecretMetadataResponse::from)
+                .collect(),
+            next_cursor,
+        })
+    }
+
+    /// List a secret's versions, newest first, a page at a time
+    pub async fn list_secret_versions(
+        db: &PgPool,
+        name: &str,
+        query: ListSecretVersionsQuery,
+    ) -> Result<ListSecretVersionsResponse, AppError> {
+        let secret = SecretRepository::get_secret_by_name(db, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        let after_id = query
+            .cursor
+            .as_deref()
+            .map(decode_version_cursor)
+            .transpose()?;
+        let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+
+        let mut versions =
+            SecretRepository::list_secret_versions(db, secret.id, after_id, limit + 1).await?;
+        let next_cursor = if versions.len() as i64 > limit {
+            versions.truncate(limit as usize);
+            versions.last().map(|last| hex::encode(last.id.to_string()))
+        } else {
+            None
+        };
+
+        let versions = versions
+            .into_iter()
+            .map(|version| SecretVersionMetadataResponse {
+                current: secret.current_version.as_ref() == Some(&version.version_tag),
+                previous: secret.previous_version.as_ref() == Some(&version.version_tag),
+                version_tag: version.version_tag,
+                fingerprint: version.sha256sum,
+                deleted: version.deleted,
+                expire_at: version.expire_at,
+                created_at: version.created_at,
+                destroyed_at: version.destroyed_at,
+            })
+            .collect();
+
+        Ok(ListSecretVersionsResponse {
+            name: secret.name,
+            current_version: secret.current_version,
+            previous_version: secret.previous_version,
+            versions,
+            next_cursor,
+        })
+    }
+
+    /// List the secrets and subfolders directly under a folder, where `prefix` is empty
+    /// for the root or ends with `/`
+    pub async fn list_folder(db: &PgPool, prefix: &str) -> Result<FolderResponse, AppError> {
+        let children = SecretRepository::get_folder_children(db, prefix).await?;
+
+        let mut folders = Vec::new();
+        let mut secrets = Vec::new();
+        for (child, is_folder) in children {
+            if is_folder {
+                folders.push(child);
+            } else {
+                secrets.push(child);
+            }
+        }
+
+        Ok(FolderResponse {
+            prefix: prefix.to_string(),
+            folders,
+            secrets,
+        })
+    }
+
+    /// Destroy every secret under a folder in one transaction, provided the client may
+    /// destroy each of them
+    pub async fn destroy_folder(
+        state: &Arc<AppState>,
+        identity: &ClientIdentity,
+        prefix: &str,
+    ) -> Result<DestroyFolderResponse, AppError> {
+        let destroyed_at = Utc::now();
+        let mut tx = state.db.begin().await?;
+
+        let secrets = SecretRepository::get_secrets_by_prefix_for_update(&mut tx, prefix).await?;
+        if secrets.is_empty() {
+            return Err(AppError::NotFoundError);
+        }
+        let names: Vec<&str> = secrets.iter().map(|secret| secret.name.as_str()).collect();
+        PolicyService::authorize_all(&state.db, identity, Capability::Admin, &names).await?;
+
+        let mut responses = Vec::with_capacity(secrets.len());
+        let mut dek_ids = Vec::new();
+        for secret in secrets {
+            let (response, secret_dek_ids) =
+                Self::destroy_locked_secret(&mut tx, secret, destroyed_at).await?;
+            responses.push(response);
+            dek_ids.extend(secret_dek_ids);
+        }
+
+        tx.commit().await?;
+
+        for dek_id in dek_ids {
+            state.keyring.dek_cache().evict(dek_id);
+        }
+        info!(prefix, secrets = responses.len(), "folder destroyed");
+
+        Ok(DestroyFolderResponse {
+            prefix: prefix.to_string(),
+            secrets: responses,
+            destroyed_at,
+        })
+    }
+
+    /// Deletes a secret locked by the caller and the DEKs of its versions, returning the
+    /// IDs of the DEKs to evict from the cache once the transaction commits.
+    pub async fn destroy_locked_secret(
+        tx: &mut Transaction<'_, Postgres>,
+        secret: Secret,
+        destroyed_at: DateTime<Utc>,
+    ) -> Result<(DestroySecretResponse, Vec<i32>), AppError> {
+        let versions = SecretRepository::get_secret_versions_for_update(tx, secret.id).await?;
+
+        // Versions reference their DEKs, so they are deleted (with the secret) first.
+        SecretRepository::delete_secret(tx, secret.id).await?;
+
+        let mut destroyed_versions = Vec::new();
+        let mut dek_ids = Vec::new();
+        for version in versions {
+            let Some(dek_id) = version.dek_id else {
+                continue;
+            };
+            let dek = DekRepository::delete_dek(tx, dek_id).await?;
+            ShredRecordRepository::create_shred_record(
+                tx,
+                &secret.name,
+                &version.version_tag,
+                &dek,
+            )
+            .await?;
+            destroyed_versions.push(version.version_tag);
+            dek_ids.push(dek_id);
+        }
+
+        Ok((
+            DestroySecretResponse {
+                name: secret.name,
+                destroyed_versions,
+                destroyed_at,
+            },
+            dek_ids,
+        ))
+    }
+
+    /// Make an existing version current, with the version it replaces as previous
+    pub async fn promote_secret_version(
+        state: &Arc<AppState>,
+        name: &str,
+        tag: &str,
+    ) -> Result<PromoteSecretVersionResponse, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        // Proxied secrets always serve what the external vault returned last.
+        if secret.vault_connection_id.is_some() {
+            return Err(AppError::MethodNotAllowed);
+        }
+
+        let version =
+            SecretRepository::get_secret_version_by_tag_for_update(&mut tx, secret.id, tag)
+                .await?
+                .filter(|version| !version.deleted)
+                .ok_or(AppError::NotFoundError)?;
+        if version.destroyed_at.is_some() {
+            return Err(AppError::InvalidInput(
+                "A destroyed version cannot be made current".to_string(),
+            ));
+        }
+
+        if secret.current_version.as_deref() == Some(tag) {
+            return Ok(PromoteSecretVersionResponse {
+                name: secret.name,
+                current_version: version.version_tag,
+                previous_version: secret.previous_version,
+            });
+        }
+
+        let previous_version = secret.current_version;
+        SecretRepository::update_secret_versions(&mut tx, secret.id, tag, previous_version.clone())
+            .await?;
+
+        tx.commit().await?;
+        info!(secret = %secret.name, version = tag, "secret version promoted");
+
+        Ok(PromoteSecretVersionResponse {
+            name: secret.name,
+            current_version: version.version_tag,
+            previous_version,
+        })
+    }
+
+    /// Swap the current and previous versions of a secret
+    pub async fn rollback_secret(
+        state: &Arc<AppState>,
+        name: &str,
+    ) -> Result<PromoteSecretVersionResponse, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        if secret.vault_connection_id.is_some() {
+            return Err(AppError::MethodNotAllowed);
+        }
+
+        // Deleting or destroying the previous version clears it, so it is still usable.
+        let current_version = secret.previous_version.ok_or_else(|| {
+            AppError::InvalidInput("The secret has no previous version".to_string())
+        })?;
+        SecretRepository::update_secret_versions(
+            &mut tx,
+            secret.id,
+            &current_version,
+            secret.current_version.clone(),
+        )
+        .await?;
+
+        tx.commit().await?;
+        info!(secret = %secret.name, version = %current_version, "secret rolled back");
+
+        Ok(PromoteSecretVersionResponse {
+            name: secret.name,
+            current_version,
+            previous_version: secret.current_version,
+        })
+    }
+
+    /// Soft-delete a secret, hiding it until it is restored or purged
+    pub async fn delete_secret(
+        state: &Arc<AppState>,
+        name: &str,
+    ) -> Result<DeleteSecretResponse, AppError> {
+        let deleted_at = Utc::now();
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        SecretRepository::set_secret_deleted_at(&mut tx, secret.id, Some(deleted_at)).await?;
+
+        tx.commit().await?;
+        info!(secret = %secret.name, "secret deleted");
+
+        Ok(DeleteSecretResponse {
+            name: secret.name,
+            version_tag: None,
+            deleted_at,
+            purge_at: deleted_at + state.secret_recovery_window,
+        })
+    }
+
+    /// Soft-delete a version of a secret. The current version is only deleted if
+    /// `force` is set, in which case the previous version becomes current.
+    pub async fn delete_secret_version(
+        state: &Arc<AppState>,
+        name: &str,
+        tag: &str,
+        force: bool,
+    ) -> Result<DeleteSecretResponse, AppError> {
+        let deleted_at = Utc::now();
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        // Proxied secrets derive the next tag from the current one, which would collide
+        // with a deleted version's.
+        if secret.vault_connection_id.is_some() {
+            return Err(AppError::MethodNotAllowed);
+        }
+
+        let version =
+            SecretRepository::get_secret_version_by_tag_for_update(&mut tx, secret.id, tag)
+                .await?
+                .filter(|version| !version.deleted)
+                .ok_or(AppError::NotFoundError)?;
+
+        if secret.current_version.as_deref() == Some(tag) {
+            if !force {
+                return Err(AppError::InvalidInput(
+                    "The current version of a secret cannot be deleted without `force`".to_string(),
+                ));
+            }
+            SecretRepository::update_secret_current_version(
+                &mut tx,
+                secret.id,
+                secret.previous_version.as_deref(),
+                None,
+            )
+            .await?;
+        } else if secret.previous_version.as_deref() == Some(tag)
+            && let Some(current_version) = &secret.current_version
+        {
+            SecretRepository::update_secret_versions(&mut tx, secret.id, current_version, None)
+                .await?;
+        }
+        SecretRepository::set_secret_version_deleted_at(&mut tx, version.id, Some(deleted_at))
+            .await?;
+
+        tx.commit().await?;
+        info!(secret = %secret.name, version = tag, "secret version deleted");
+
+        Ok(DeleteSecretResponse {
+            name: secret.name,
+            version_tag: Some(version.version_tag),
+            deleted_at,
+            purge_at: deleted_at + state.secret_recovery_window,
+        })
+    }
+
+    /// Restore a soft-deleted secret within the recovery window
+    pub async fn restore_secret(
+        state: &Arc<AppState>,
+        name: &str,
+    ) -> Result<RestoreSecretResponse, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_any_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .filter(|secret| {
+                secret
+                    .deleted_at
+                    .is_some_and(|deleted_at| Self::is_recoverable(state, deleted_at))
+            })
+            .ok_or(AppError::NotFoundError)?;
+        SecretRepository::set_secret_deleted_at(&mut tx, secret.id, None).await?;
+
+        tx.commit().await?;
+        info!(secret = %secret.name, "secret restored");
+
+        Ok(RestoreSecretResponse {
+            name: secret.name,
+            version_tag: None,
+            current_version: secret.current_version,
+        })
+    }
+
+    /// Restore a soft-deleted version within the recovery window. It only becomes
+    /// current if the secret has no current version.
+    pub async fn restore_secret_version(
+        state: &Arc<AppState>,
+        name: &str,
+        tag: &str,
+    ) -> Result<RestoreSecretResponse, AppError> {
+        let mut tx = state.db.begin().await?;
+
+        let mut secret = SecretRepository::get_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+        let version =
+            SecretRepository::get_secret_version_by_tag_for_update(&mut tx, secret.id, tag)
+                .await?
+                .filter(|version| {
+                    version.deleted
+                        && version
+                            .deleted_at
+                            .is_some_and(|deleted_at| Self::is_recoverable(state, deleted_at))
+                })
+                .ok_or(AppError::NotFoundError)?;
+        SecretRepository::set_secret_version_deleted_at(&mut tx, version.id, None).await?;
+
+        if secret.current_version.is_none() {
+            SecretRepository::update_secret_current_version(&mut tx, secret.id, Some(tag), None)
+                .await?;
+            secret.current_version = Some(version.version_tag.clone());
+        }
+
+        tx.commit().await?;
+        info!(secret = %secret.name, version = tag, "secret version restored");
+
+        Ok(RestoreSecretResponse {
+            name: secret.name,
+            version_tag: Some(version.version_tag),
+            current_version: secret.current_version,
+        })
+    }
+
+    /// Whether something soft-deleted at `deleted_at` is still within the recovery
+    /// window, even if the purger has not caught up with it yet.
+    fn is_recoverable(state: &AppState, deleted_at: DateTime<Utc>) -> bool {
+        Utc::now() < deleted_at + state.secret_recovery_window
+    }
+
+    /// Destroy a single, non-current version of a secret by deleting its DEK
+    pub async fn destroy_secret_version(
+        state: &Arc<AppState>,
+        name: &str,
+        tag: &str,
+    ) -> Result<DestroySecretResponse, AppError> {
+        let destroyed_at = Utc::now();
+        let mut tx = state.db.begin().await?;
+
+        let secret = SecretRepository::get_secret_by_name_for_update(&mut tx, name)
+            .await?
+            .ok_or(AppError::NotFoundError)?;
+
+        if secret.current_version.as_deref() == Some(tag) {
+            return Err(AppError::InvalidInput(
+                "The current version of a secret cannot be destroyed".to_string(),
+            ));
+        }
+
+        let version =
+            SecretRepository::get_secret_version_by_tag_for_update(&mut tx, secret.id, tag)
+                .await?
+                .ok_or(AppError::NotFoundError)?;
+        let dek_id = version.dek_id()?;
+
+        SecretRepository::destroy_secret_version(&mut tx, version.id, destroyed_at).await?;
+        let dek = DekRepository::delete_dek(&mut tx, dek_id).await?;
+        ShredRecordRepository::create_shred_record(&mut tx, &secret.name, tag, &dek).await?;
+
+        if secret.previous_version.as_deref() == Some(tag)
+            && let Some(current_version) = &secret.current_version
+        {
+            SecretRepository::update_secret_versions(&mut tx, secret.id, current_version, None)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        state.keyring.dek_cache().evict(dek_id);
+        info!(secret = %secret.name, version = tag, "secret version destroyed");
+
+        Ok(DestroySecretResponse {
+            name: secret.name,
+            destroyed_versions: vec![version.version_tag],
+            destroyed_at,
+        })
+    }
+
     async fn refresh_proxied_secret(
         state: &Arc<AppState>,
         secret: Secret,
         vc_id: i32,
     ) -> Result<SecretResponse, AppError> {
-        let connection = ConnectionService::get_vault_connection_config_by_id(
-            &state.db,
-            &state.kms_client,
-            vc_id,
-        )
-        .await?;
+        let connection =
+            ConnectionService::get_vault_connection_config_by_id(&state.db, &state.keyring, vc_id)
+                .await?;
 
         let factory = state
             .provider_factories
@@ -236,7 +746,6 @@ impl SecretService {
         ttl: Option<i32>,
     ) -> Result<String, AppError> {
         let expire_at = Utc::now() + Duration::seconds(ttl.unwrap_or(DEFAULT_TTL_SECONDS) as i64);
-        let new_sha256sum = crypto::sha256_hash(new_value);
 
         if let Some(current_version_tag) = &secret.current_version {
             let current_version = SecretRepository::get_secret_version_by_tag(
@@ -247,8 +756,11 @@ impl SecretService {
             .await?
             .ok_or(AppError::NotFoundError)?;
 
-            // If the hash is the same, we just update the expiry and we're done.
-            if Some(new_sha256sum) == current_version.sha256sum {
+            // If the fingerprint is the same, we just update the expiry and we're done.
+            let unchanged = current_version.sha256sum.as_deref().is_some_and(|stored| {
+                crypto::fingerprint_matches(&state.keyring, stored, new_value)
+            });
+            if unchanged {
                 SecretRepository::update_secret_version_expiry(tx, current_version.id, expire_at)
                     .await?;
                 SecretRepository::update_secret_expiry(tx, secret.id, expire_at).await?;
@@ -264,13 +776,19 @@ impl SecretService {
                 .unwrap_or_else(|| "v".to_string()),
         );
 
-        let encrypted_payload = crypto::encrypt(tx, &state.kms_client, new_value).await?;
+        let encrypted_payload = crypto::encrypt(
+            tx,
+            &state.keyring,
+            new_value,
+            &EncryptionContext::secret_version(&secret.name, &new_version_tag),
+        )
+        .await?;
 
         SecretRepository::create_secret_version(
             tx,
             secret.id,
             &new_version_tag,
-            &encrypted_payload.sha256sum,
+            &encrypted_payload.fingerprint,
             &encrypted_payload.encrypted_blob,
             encrypted_payload.dek_id,
         )
@@ -295,7 +813,7 @@ impl SecretService {
     ) -> Result<(Zeroizing<String>, i32), AppError> {
         let connection = ConnectionService::get_vault_connection(
             &state.db,
-            &state.kms_client,
+            &state.keyring,
             vault_connection_public_id,
         )
         .await?;
@@ -321,12 +839,18 @@ impl SecretService {
     /// Helper method to decrypt secret values
     async fn decrypt_secret_value(
         db: &PgPool,
-        kms_client: &Arc<KmsClient>,
-        encrypted_secret: &str,
-        dek_id: i32,
+        keyring: &Keyring,
+        name: &str,
+        version: &SecretVersion,
     ) -> Result<Zeroizing<String>, AppError> {
-        let decrypted_value_bytes =
-            crypto::decrypt(db, kms_client, dek_id, encrypted_secret).await?;
+        let decrypted_value_bytes = crypto::decrypt(
+            db,
+            keyring,
+            version.dek_id()?,
+            &version.encrypted_secret,
+            &EncryptionContext::secret_version(name, &version.version_tag),
+        )
+        .await?;
         Ok(Zeroizing::new(
             String::from_utf8(decrypted_value_bytes).map_err(|e| {
                 AppError::CryptoError(format!(
@@ -353,3 +877,30 @@ impl SecretService {
         }
     }
 }
+
+/// Encodes a listing position as an opaque cursor.
+fn encode_cursor(created_at: DateTime<Utc>, id: i32) -> String {
+    hex::encode(format!("{}:{}", created_at.timestamp_micros(), id))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, i32), AppError> {
+    let invalid = || AppError::InvalidInput("Invalid cursor".to_string());
+    let decoded =
+        String::from_utf8(hex::decode(cursor).map_err(|_| invalid())?).map_err(|_| invalid())?;
+    let (micros, id) = decoded.split_once(':').ok_or_else(invalid)?;
+    let created_at = micros
+        .parse()
+        .ok()
+        .and_then(DateTime::from_timestamp_micros)
+        .ok_or_else(invalid)?;
+    let id = id.parse().map_err(|_| invalid())?;
+    Ok((created_at, id))
+}
+
+fn decode_version_cursor(cursor: &str) -> Result<i32, AppError> {
+    hex::decode(cursor)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| AppError::InvalidInput("Invalid cursor".to_string()))
+}
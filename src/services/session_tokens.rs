@@ -0,0 +1,119 @@
+use crate::{
+    errors::AppError,
+    models::{AuthMethod, ClientIdentity, LoginRequest, LoginResponse},
+    repositories::session_tokens::SessionTokenRepository,
+    state::AppState,
+};
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+pub const SESSION_TOKEN_PREFIX: &str = "lvt_";
+
+const SESSION_TOKEN_SIZE: usize = 32;
+const DEFAULT_SESSION_TOKEN_TTL_SECS: i64 = 900;
+
+pub struct SessionTokenService;
+
+impl SessionTokenService {
+    /// Periodically delete expired and revoked session tokens
+    pub fn spawn_pruner(state: Arc<AppState>, interval: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match SessionTokenRepository::delete_expired_session_tokens(&state.db).await {
+                    Ok(0) => {}
+                    Ok(pruned) => info!(pruned, "expired and revoked session tokens pruned"),
+                    Err(e) => error!("Failed to prune session tokens: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Issue a session token to a client that signed its login request
+    pub async fn login(
+        db: &PgPool,
+        identity: &ClientIdentity,
+        request: LoginRequest,
+    ) -> Result<LoginResponse, AppError> {
+        if identity.auth_method != AuthMethod::Signature {
+            warn!(client = %identity.name, auth_method = ?identity.auth_method, "login requires a signed request");
+            return Err(AppError::Forbidden);
+        }
+
+        let mut token_bytes = [0u8; SESSION_TOKEN_SIZE];
+        OsRng.fill_bytes(&mut token_bytes);
+        let token = format!("{}{}", SESSION_TOKEN_PREFIX, hex::encode(token_bytes));
+
+        let ttl_secs = request.ttl_secs.unwrap_or(DEFAULT_SESSION_TOKEN_TTL_SECS);
+        let session_token = SessionTokenRepository::create_session_token(
+            db,
+            &hash_token(&token),
+            &identity.name,
+            identity.key_id.as_deref(),
+            identity.is_root,
+            request.capabilities.as_deref(),
+            Utc::now() + Duration::seconds(ttl_secs),
+        )
+        .await?;
+
+        info!(
+            session_token_id = session_token.id,
+            client = %session_token.client_name,
+            expires_at = %session_token.expires_at,
+            "session token issued"
+        );
+        Ok(LoginResponse {
+            token,
+            expires_at: session_token.expires_at,
+            capabilities: session_token.capabilities,
+        })
+    }
+
+    /// Revoke a session token; requests bearing it are rejected from then on
+    pub async fn logout(db: &PgPool, token: &str) -> Result<(), AppError> {
+        let revoked = SessionTokenRepository::revoke_session_token(db, &hash_token(token)).await?;
+        if revoked == 0 {
+            return Err(AppError::NotFoundError);
+        }
+        Ok(())
+    }
+
+    /// Look up the identity a usable session token was issued to
+    pub async fn resolve_session_token(
+        db: &PgPool,
+        token: &str,
+    ) -> Result<ClientIdentity, AppError> {
+        let session_token =
+            SessionTokenRepository::get_session_token_by_hash(db, &hash_token(token))
+                .await?
+                .ok_or(AppError::Unauthorized)?;
+
+        if !session_token.is_usable(Utc::now()) {
+            warn!(
+                session_token_id = session_token.id,
+                "rejected expired or revoked session token"
+            );
+            return Err(AppError::Unauthorized);
+        }
+
+        Ok(ClientIdentity {
+            key_id: session_token.key_id,
+            name: session_token.client_name,
+            is_root: session_token.is_root,
+            auth_method: AuthMethod::SessionToken,
+            scope: session_token.capabilities,
+            namespace: None,
+            audience: None,
+        })
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
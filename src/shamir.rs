@@ -0,0 +1,240 @@
+use crate::errors::AppError;
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use zeroize::Zeroizing;
+
+/// One share of a secret split with Shamir's secret sharing over GF(2^8).
+///
+/// Each byte of the secret is the constant term of its own random polynomial of degree
+/// `threshold - 1`; a share holds every polynomial evaluated at the share's `x`.
+pub struct Share {
+    pub x: u8,
+    pub y: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    /// Encodes the share as hex of `x || y`.
+    pub fn to_hex(&self) -> Zeroizing<String> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(self.y.len() + 1));
+        bytes.push(self.x);
+        bytes.extend_from_slice(&self.y);
+        Zeroizing::new(hex::encode(&*bytes))
+    }
+
+    pub fn from_hex(share_hex: &str) -> Result<Self, AppError> {
+        let bytes = Zeroizing::new(
+            hex::decode(share_hex.trim())
+                .map_err(|_| AppError::InvalidInput("Invalid share format".to_string()))?,
+        );
+        match bytes.split_first() {
+            Some((&x, y)) if x != 0 && !y.is_empty() => Ok(Self {
+                x,
+                y: Zeroizing::new(y.to_vec()),
+            }),
+            _ => Err(AppError::InvalidInput("Invalid share format".to_string())),
+        }
+    }
+}
+
+/// Splits `secret` into `share_count` shares, any `threshold` of which reconstruct it.
+pub fn split(secret: &[u8], threshold: u8, share_count: u8) -> Result<Vec<Share>, AppError> {
+    if threshold == 0 || threshold > share_count {
+        return Err(AppError::InvalidInput(
+            "Threshold must be between 1 and the number of shares".to_string(),
+        ));
+    }
+
+    let mut shares: Vec<Share> = (1..=share_count)
+        .map(|x| Share {
+            x,
+            y: Zeroizing::new(Vec::with_capacity(secret.len())),
+        })
+        .collect();
+
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+    for &secret_byte in secret {
+        coefficients[0] = secret_byte;
+        OsRng.fill_bytes(&mut coefficients[1..]);
+
+        for share in &mut shares {
+            // Horner's method, highest coefficient first.
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0u8, |acc, &coefficient| gf_mul(acc, share.x) ^ coefficient);
+            share.y.push(y);
+        }
+    }
+
+    Ok(shares)
+}
+
+/// Reconstructs a secret from at least `threshold` distinct shares by Lagrange
+/// interpolation at zero. Too few shares yield a wrong secret, not an error.
+pub fn combine(shares: &[Share]) -> Result<Zeroizing<Vec<u8>>, AppError> {
+    let Some(first) = shares.first() else {
+        return Err(AppError::InvalidInput("No shares provided".to_string()));
+    };
+    let secret_len = first.y.len();
+    if shares.iter().any(|share| share.y.len() != secret_len) {
+        return Err(AppError::InvalidInput(
+            "Shares have mismatched lengths".to_string(),
+        ));
+    }
+    for (i, share) in shares.iter().enumerate() {
+        // The polynomial at zero is the secret itself, so no valid share has x = 0.
+        if share.x == 0 {
+            return Err(AppError::InvalidInput("Invalid share format".to_string()));
+        }
+        if shares[..i].iter().any(|other| other.x == share.x) {
+            return Err(AppError::InvalidInput("Duplicate share".to_string()));
+        }
+    }
+
+    let mut secret = Zeroizing::new(vec![0u8; secret_len]);
+    for (i, share) in shares.iter().enumerate() {
+        let mut basis = 1u8;
+        for (j, other) in shares.iter().enumerate() {
+            if i != j {
+                basis = gf_mul(basis, gf_div(other.x, other.x ^ share.x));
+            }
+        }
+        for (secret_byte, &y) in secret.iter_mut().zip(share.y.iter()) {
+            *secret_byte ^= gf_mul(y, basis);
+        }
+    }
+
+    Ok(secret)
+}
+
+/// Multiplication in GF(2^8) with the AES polynomial, without data-dependent branches.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_div(a: u8, b: u8) -> u8 {
+    // b^254 is the inverse of b in GF(2^8).
+    let mut inverse = 1u8;
+    let mut power = b;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            inverse = gf_mul(inverse, power);
+        }
+        power = gf_mul(power, power);
+        exponent >>= 1;
+    }
+    gf_mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"an unseal key of thirty-two byte";
+
+    fn clone_share(share: &Share) -> Share {
+        Share {
+            x: share.x,
+            y: share.y.clone(),
+        }
+    }
+
+    #[test]
+    fn combines_any_threshold_of_shares() {
+        let shares = split(SECRET, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1], [1, 2, 3]] {
+            let subset: Vec<Share> = subset.iter().map(|&i| clone_share(&shares[i])).collect();
+            assert_eq!(combine(&subset).unwrap().as_slice(), SECRET);
+        }
+        assert_eq!(combine(&shares).unwrap().as_slice(), SECRET);
+    }
+
+    #[test]
+    fn threshold_of_one_copies_the_secret() {
+        let shares = split(SECRET, 1, 3).unwrap();
+        for share in &shares {
+            assert_eq!(share.y.as_slice(), SECRET);
+        }
+    }
+
+    #[test]
+    fn fewer_shares_than_the_threshold_yield_a_wrong_secret() {
+        let shares = split(SECRET, 3, 5).unwrap();
+        let subset = [clone_share(&shares[0]), clone_share(&shares[1])];
+        assert_ne!(combine(&subset).unwrap().as_slice(), SECRET);
+    }
+
+    #[test]
+    fn rejects_invalid_split_parameters() {
+        assert!(split(SECRET, 0, 3).is_err());
+        assert!(split(SECRET, 4, 3).is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_shares() {
+        let shares = split(SECRET, 2, 3).unwrap();
+        let duplicated = [clone_share(&shares[0]), clone_share(&shares[0])];
+        assert!(combine(&duplicated).is_err());
+    }
+
+    #[test]
+    fn rejects_zero_x_shares() {
+        assert!(Share::from_hex("00abcdef").is_err());
+
+        let shares = split(SECRET, 2, 3).unwrap();
+        let zero = Share {
+            x: 0,
+            y: shares[1].y.clone(),
+        };
+        assert!(combine(&[clone_share(&shares[0]), zero]).is_err());
+    }
+
+    #[test]
+    fn rejects_mismatched_and_missing_shares() {
+        let shares = split(SECRET, 2, 3).unwrap();
+        let short = Share {
+            x: shares[1].x,
+            y: Zeroizing::new(shares[1].y[1..].to_vec()),
+        };
+        assert!(combine(&[clone_share(&shares[0]), short]).is_err());
+        assert!(combine(&[]).is_err());
+    }
+
+    #[test]
+    fn hex_encoding_round_trips() {
+        let shares = split(SECRET, 2, 3).unwrap();
+        let decoded = Share::from_hex(&shares[2].to_hex()).unwrap();
+        assert_eq!(decoded.x, shares[2].x);
+        assert_eq!(decoded.y.as_slice(), shares[2].y.as_slice());
+
+        assert!(Share::from_hex("not hex").is_err());
+        assert!(Share::from_hex("01").is_err());
+    }
+
+    #[test]
+    fn gf_mul_matches_the_aes_field() {
+        // FIPS-197 section 4.2.
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+        assert_eq!(gf_mul(0x00, 0xff), 0x00);
+        assert_eq!(gf_mul(0x01, 0xab), 0xab);
+    }
+
+    #[test]
+    fn gf_div_inverts_gf_mul() {
+        for a in 0..=255u8 {
+            for b in 1..=255u8 {
+                assert_eq!(gf_mul(gf_div(a, b), b), a);
+            }
+        }
+    }
+}
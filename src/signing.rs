@@ -0,0 +1,239 @@
+use crate::errors::AppError;
+use crate::models::SignatureAlgorithm;
+use ecdsa::signature::Verifier;
+use p256::pkcs8::DecodePublicKey;
+
+/// The public key a client signs its requests with. The signature algorithm is
+/// determined by the key: ECDSA P-256 with SHA-256, ECDSA P-384 with SHA-384 or Ed25519.
+#[derive(Debug, Clone)]
+pub enum ClientVerifyingKey {
+    EcdsaP256(p256::ecdsa::VerifyingKey),
+    EcdsaP384(p384::ecdsa::VerifyingKey),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl ClientVerifyingKey {
+    /// Parses a PEM-encoded SubjectPublicKeyInfo, or a hex-encoded DER
+    /// SubjectPublicKeyInfo, SEC1 P-256 or P-384 point or raw 32-byte Ed25519 key.
+    pub fn parse(public_key: &str) -> Result<Self, AppError> {
+        let public_key = public_key.trim();
+        if public_key.starts_with("-----BEGIN") {
+            return Self::from_spki_pem(public_key);
+        }
+
+        let bytes = hex::decode(public_key)
+            .map_err(|e| AppError::CryptoError(format!("Invalid public key hex: {}", e)))?;
+        match bytes.first() {
+            // A DER SEQUENCE, never the first byte of a SEC1 point.
+            Some(0x30) if bytes.len() != ed25519_dalek::PUBLIC_KEY_LENGTH => {
+                Self::from_spki_der(&bytes)
+            }
+            _ => Self::from_raw_bytes(&bytes),
+        }
+    }
+
+    pub fn algorithm(&self) -> SignatureAlgorithm {
+        match self {
+            Self::EcdsaP256(_) => SignatureAlgorithm::EcdsaP256,
+            Self::EcdsaP384(_) => SignatureAlgorithm::EcdsaP384,
+            Self::Ed25519(_) => SignatureAlgorithm::Ed25519,
+        }
+    }
+
+    /// Verifies `signature` over `message`. ECDSA signatures may be fixed-size `r || s`
+    /// or DER-encoded.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), AppError> {
+        let verified = match self {
+            Self::EcdsaP256(key) => {
+                let signature = p256::ecdsa::Signature::from_slice(signature)
+                    .or_else(|_| p256::ecdsa::Signature::from_der(signature))
+                    .map_err(|_| invalid_signature())?;
+                key.verify(message, &signature).is_ok()
+            }
+            Self::EcdsaP384(key) => {
+                let signature = p384::ecdsa::Signature::from_slice(signature)
+                    .or_else(|_| p384::ecdsa::Signature::from_der(signature))
+                    .map_err(|_| invalid_signature())?;
+                key.verify(message, &signature).is_ok()
+            }
+            Self::Ed25519(key) => {
+                let signature = ed25519_dalek::Signature::from_slice(signature)
+                    .map_err(|_| invalid_signature())?;
+                key.verify_strict(message, &signature).is_ok()
+            }
+        };
+
+        if !verified {
+            return Err(AppError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn from_spki_pem(pem: &str) -> Result<Self, AppError> {
+        if let Ok(key) = p256::ecdsa::VerifyingKey::from_public_key_pem(pem) {
+            return Ok(Self::EcdsaP256(key));
+        }
+        if let Ok(key) = p384::ecdsa::VerifyingKey::from_public_key_pem(pem) {
+            return Ok(Self::EcdsaP384(key));
+        }
+        ed25519_dalek::VerifyingKey::from_public_key_pem(pem)
+            .map(Self::Ed25519)
+            .map_err(|_| unsupported_key())
+    }
+
+    fn from_spki_der(der: &[u8]) -> Result<Self, AppError> {
+        if let Ok(key) = p256::ecdsa::VerifyingKey::from_public_key_der(der) {
+            return Ok(Self::EcdsaP256(key));
+        }
+        if let Ok(key) = p384::ecdsa::VerifyingKey::from_public_key_der(der) {
+            return Ok(Self::EcdsaP384(key));
+        }
+        ed25519_dalek::VerifyingKey::from_public_key_der(der)
+            .map(Self::Ed25519)
+            .map_err(|_| unsupported_key())
+    }
+
+    /// Tells the key types apart by length: 32 bytes for Ed25519, 33 or 65 for a
+    /// compressed or uncompressed P-256 point and 49 or 97 for a P-384 point.
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, AppError> {
+        match bytes.len() {
+            ed25519_dalek::PUBLIC_KEY_LENGTH => {
+                let bytes: &[u8; ed25519_dalek::PUBLIC_KEY_LENGTH] =
+                    bytes.try_into().map_err(|_| unsupported_key())?;
+                ed25519_dalek::VerifyingKey::from_bytes(bytes)
+                    .map(Self::Ed25519)
+                    .map_err(|_| unsupported_key())
+            }
+            33 | 65 => p256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+                .map(Self::EcdsaP256)
+                .map_err(|_| unsupported_key()),
+            49 | 97 => p384::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+                .map(Self::EcdsaP384)
+                .map_err(|_| unsupported_key()),
+            _ => Err(unsupported_key()),
+        }
+    }
+}
+
+fn invalid_signature() -> AppError {
+    AppError::InvalidInput("Invalid signature".to_string())
+}
+
+fn unsupported_key() -> AppError {
+    AppError::CryptoError(
+        "Public key is not a valid P-256, P-384 or Ed25519 public key".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ecdsa::signature::Signer;
+    use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+    const MESSAGE: &[u8] = b"GET /v1/secrets/payments/api-key";
+
+    fn p256_key() -> p256::ecdsa::SigningKey {
+        p256::ecdsa::SigningKey::from_slice(&[1; 32]).unwrap()
+    }
+
+    fn p384_key() -> p384::ecdsa::SigningKey {
+        p384::ecdsa::SigningKey::from_slice(&[1; 48]).unwrap()
+    }
+
+    fn ed25519_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[7; 32])
+    }
+
+    #[test]
+    fn parses_p256_keys_in_every_encoding() {
+        let verifying_key = p256_key().verifying_key().to_owned();
+        let encodings = [
+            hex::encode(verifying_key.to_encoded_point(false).as_bytes()),
+            hex::encode(verifying_key.to_encoded_point(true).as_bytes()),
+            hex::encode(verifying_key.to_public_key_der().unwrap().as_bytes()),
+            verifying_key.to_public_key_pem(LineEnding::LF).unwrap(),
+        ];
+        for encoding in encodings {
+            let key = ClientVerifyingKey::parse(&encoding).unwrap();
+            assert_eq!(key.algorithm(), SignatureAlgorithm::EcdsaP256);
+        }
+    }
+
+    #[test]
+    fn verifies_fixed_size_and_der_p256_signatures() {
+        let signing_key = p256_key();
+        let key = ClientVerifyingKey::parse(&hex::encode(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes(),
+        ))
+        .unwrap();
+
+        let signature: p256::ecdsa::Signature = signing_key.sign(MESSAGE);
+        key.verify(MESSAGE, &signature.to_bytes()).unwrap();
+        key.verify(MESSAGE, signature.to_der().as_bytes()).unwrap();
+        assert!(matches!(
+            key.verify(b"another message", &signature.to_bytes()),
+            Err(AppError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn parses_and_verifies_p384_keys() {
+        let signing_key = p384_key();
+        let verifying_key = signing_key.verifying_key().to_owned();
+        let key =
+            ClientVerifyingKey::parse(&verifying_key.to_public_key_pem(LineEnding::LF).unwrap())
+                .unwrap();
+        assert_eq!(key.algorithm(), SignatureAlgorithm::EcdsaP384);
+
+        let signature: p384::ecdsa::Signature = signing_key.sign(MESSAGE);
+        key.verify(MESSAGE, &signature.to_bytes()).unwrap();
+
+        let key = ClientVerifyingKey::parse(&hex::encode(
+            verifying_key.to_encoded_point(true).as_bytes(),
+        ))
+        .unwrap();
+        assert_eq!(key.algorithm(), SignatureAlgorithm::EcdsaP384);
+    }
+
+    #[test]
+    fn parses_and_verifies_ed25519_keys() {
+        let signing_key = ed25519_key();
+        let verifying_key = signing_key.verifying_key();
+        for encoding in [
+            hex::encode(verifying_key.as_bytes()),
+            verifying_key.to_public_key_pem(LineEnding::LF).unwrap(),
+        ] {
+            let key = ClientVerifyingKey::parse(&encoding).unwrap();
+            assert_eq!(key.algorithm(), SignatureAlgorithm::Ed25519);
+
+            let signature = signing_key.sign(MESSAGE);
+            key.verify(MESSAGE, &signature.to_bytes()).unwrap();
+            assert!(matches!(
+                key.verify(b"another message", &signature.to_bytes()),
+                Err(AppError::Unauthorized)
+            ));
+            assert!(matches!(
+                key.verify(MESSAGE, &signature.to_bytes()[1..]),
+                Err(AppError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        for key in [
+            "",
+            "not hex",
+            "abcd",
+            &hex::encode([0x04; 65]),
+            &hex::encode([0x30; 40]),
+            "-----BEGIN PUBLIC KEY-----\nAAAA\n-----END PUBLIC KEY-----",
+        ] {
+            assert!(ClientVerifyingKey::parse(key).is_err(), "{key:?}");
+        }
+    }
+}
@@ -1,6 +1,11 @@
-use aws_sdk_kms::Client as KmsClient;
+use crate::jwt::JwtVerifier;
+use crate::keyring::Keyring;
+use crate::keyring::shamir::Seal;
+use crate::nonce_cache::NonceCache;
+use crate::rate_limit::RateLimiter;
+use crate::services::health::ReadinessCache;
+use crate::signing::ClientVerifyingKey;
 use lockset_vault_provider::VaultProviderFactory;
-use p256::ecdsa::VerifyingKey;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -8,7 +13,22 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
-    pub kms_client: Arc<KmsClient>,
-    pub auth_verifying_key: Arc<VerifyingKey>,
+    pub keyring: Arc<Keyring>,
+    /// Present when the `shamir` key wrapping backend is enabled.
+    pub seal: Option<Arc<Seal>>,
+    /// Verifying key of requests signed without an `X-Key-Id`, from `AUTH_PUBLIC_KEY`.
+    pub auth_verifying_key: Option<Arc<ClientVerifyingKey>>,
+    /// Present when bearer token authentication is configured with `JWT_ISSUER`.
+    pub jwt_verifier: Option<Arc<JwtVerifier>>,
+    /// Nonces of recently accepted requests on this node.
+    pub nonce_cache: Arc<NonceCache>,
+    /// Whether nonces are also recorded in Postgres, rejecting replays across replicas.
+    pub replay_cache_shared: bool,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub readiness_cache: Arc<ReadinessCache>,
+    /// Whether `/readyz` validates a connection of each vault provider.
+    pub readiness_check_providers: bool,
+    /// How long soft-deleted secrets and versions can be restored before they are purged.
+    pub secret_recovery_window: chrono::Duration,
     pub provider_factories: Arc<HashMap<String, Box<dyn VaultProviderFactory + Send + Sync>>>,
 }
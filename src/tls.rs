@@ -0,0 +1,258 @@
+use axum::Router;
+use axum::extract::{ConnectInfo, Request};
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use rustls::RootCertStore;
+use rustls::ServerConfig;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{error, info, warn};
+use x509_parser::extensions::GeneralName;
+
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The verified client certificate a request was received over, added to the request
+/// extensions by the TLS listener.
+#[derive(Clone, Debug)]
+pub struct ClientCertificate {
+    /// The certificate's first URI SAN (such as a SPIFFE ID), else its first DNS SAN,
+    /// else its subject common name.
+    pub name: String,
+}
+
+impl ClientCertificate {
+    fn from_der(der: &[u8]) -> Option<Self> {
+        let (_, certificate) = x509_parser::parse_x509_certificate(der).ok()?;
+
+        let names = certificate
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|san| san.value.general_names.clone())
+            .unwrap_or_default();
+        let uri = names.iter().find_map(|name| match name {
+            GeneralName::URI(uri) => Some(*uri),
+            _ => None,
+        });
+        let dns_name = names.iter().find_map(|name| match name {
+            GeneralName::DNSName(dns_name) => Some(*dns_name),
+            _ => None,
+        });
+        let common_name = certificate
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|common_name| common_name.as_str().ok());
+
+        uri.or(dns_name).or(common_name).map(|name| Self {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// The files a TLS server configuration is built from.
+#[derive(Debug, Clone)]
+pub struct TlsFiles {
+    pub cert_file: PathBuf,
+    pub key_file: PathBuf,
+    /// CA bundle client certificates are verified against; client certificates are not
+    /// requested when unset.
+    pub client_ca_file: Option<PathBuf>,
+    /// Whether connections without a client certificate are refused.
+    pub client_auth_required: bool,
+}
+
+/// A TLS server configuration that can be rebuilt from its files while serving. New
+/// connections use the latest configuration; established ones keep theirs.
+pub struct ReloadableTlsConfig {
+    files: TlsFiles,
+    current: RwLock<Arc<ServerConfig>>,
+}
+
+impl ReloadableTlsConfig {
+    pub fn load(files: TlsFiles) -> Result<Self, String> {
+        let server_config = build_server_config(&files)?;
+        Ok(Self {
+            files,
+            current: RwLock::new(server_config),
+        })
+    }
+
+    pub fn current(&self) -> Arc<ServerConfig> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Rebuilds the configuration from its files, keeping the previous one on failure.
+    pub fn reload(&self) -> Result<(), String> {
+        let server_config = build_server_config(&self.files)?;
+        *self.current.write().unwrap() = server_config;
+        Ok(())
+    }
+
+    /// Reload the configuration on `SIGHUP` and every `interval`, so that renewed
+    /// certificates are picked up without a restart
+    pub fn spawn_reloader(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut hangups = match signal(SignalKind::hangup()) {
+                Ok(hangups) => Some(hangups),
+                Err(e) => {
+                    warn!(
+                        "Failed to listen for SIGHUP, TLS reloads are periodic only: {}",
+                        e
+                    );
+                    None
+                }
+            };
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    Some(_) = async {
+                        match hangups.as_mut() {
+                            Some(hangups) => hangups.recv().await,
+                            None => std::future::pending().await,
+                        }
+                    } => {}
+                }
+                match self.reload() {
+                    Ok(()) => info!("TLS configuration reloaded"),
+                    Err(e) => error!("Failed to reload TLS configuration: {}", e),
+                }
+            }
+        })
+    }
+}
+
+fn build_server_config(files: &TlsFiles) -> Result<Arc<ServerConfig>, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let certs = load_certs(&files.cert_file)?;
+    let key = PrivateKeyDer::from_pem_file(&files.key_file).map_err(|e| {
+        format!(
+            "Failed to read private key from {}: {}",
+            files.key_file.display(),
+            e
+        )
+    })?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to configure TLS protocol versions: {}", e))?;
+    let builder = match &files.client_ca_file {
+        Some(client_ca_file) => builder.with_client_cert_verifier(build_client_verifier(
+            client_ca_file,
+            files.client_auth_required,
+            provider,
+        )?),
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {}", e))?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(Arc::new(server_config))
+}
+
+fn build_client_verifier(
+    client_ca_file: &Path,
+    client_auth_required: bool,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>, String> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(client_ca_file)? {
+        roots
+            .add(cert)
+            .map_err(|e| format!("Invalid client CA certificate: {}", e))?;
+    }
+
+    let builder = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+    let builder = if client_auth_required {
+        builder
+    } else {
+        builder.allow_unauthenticated()
+    };
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build client certificate verifier: {}", e))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read certificates from {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+/// Serves `app` over TLS, adding the remote address and verified client certificate of
+/// each connection, if any, to the extensions of its requests.
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    tls_config: Arc<ReloadableTlsConfig>,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, remote_addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+        let acceptor = TlsAcceptor::from(tls_config.current());
+        let app = app.clone();
+
+        tokio::spawn(async move {
+            let stream =
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => stream,
+                    Ok(Err(e)) => {
+                        warn!(%remote_addr, "TLS handshake failed: {}", e);
+                        return;
+                    }
+                    Err(_) => {
+                        warn!(%remote_addr, "TLS handshake timed out");
+                        return;
+                    }
+                };
+
+            let client_certificate = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certs| certs.first())
+                .and_then(|cert| ClientCertificate::from_der(cert));
+
+            let service = hyper::service::service_fn(move |mut req: Request<Incoming>| {
+                req.extensions_mut().insert(ConnectInfo(remote_addr));
+                if let Some(client_certificate) = &client_certificate {
+                    req.extensions_mut().insert(client_certificate.clone());
+                }
+                app.clone().oneshot(req)
+            });
+
+            if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                warn!(%remote_addr, "Failed to serve connection: {}", e);
+            }
+        });
+    }
+}
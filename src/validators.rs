@@ -7,3 +7,19 @@ pub fn validate_vault_config(config: &Zeroizing<String>) -> Result<(), Validatio
     }
     Ok(())
 }
+
+/// The path segment separating a secret name from a version tag in request paths, which
+/// secret names therefore may not contain.
+pub const VERSIONS_SEGMENT: &str = "versions";
+
+/// Checks the `/`-separated segments of a secret name or folder, on top of
+/// `get_secret_name_regex`.
+pub fn validate_secret_path(name: &str) -> Result<(), ValidationError> {
+    let invalid = name.split('/').any(|segment| {
+        segment.is_empty() || segment == "." || segment == ".." || segment == VERSIONS_SEGMENT
+    });
+    if invalid {
+        return Err(ValidationError::new("invalid_secret_path"));
+    }
+    Ok(())
+}